@@ -0,0 +1,106 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::highscore;
+
+// counters of what the player has done so far in the current game, which the achievements are
+// checked against
+#[derive(Default)]
+pub struct Stats {
+    pub paddle_hits: u32,
+    // seconds in a row with at least `Achievement::CROWD_BALLS` balls in play
+    pub crowd_time: f32,
+    // how far the player's paddle has moved in all, in pixels
+    pub paddle_travel: f32,
+    pub won: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Achievement {
+    HundredHits,
+    Crowd,
+    BarelyMoved,
+}
+
+impl Achievement {
+    const ALL: [Achievement; 3] = [
+        Achievement::HundredHits,
+        Achievement::Crowd,
+        Achievement::BarelyMoved,
+    ];
+    pub const CROWD_BALLS: usize = 5;
+    const CROWD_TIME: f32 = 30.0;
+
+    // name the achievement is saved under, which must never change
+    fn id(self) -> &'static str {
+        match self {
+            Achievement::HundredHits => "hundred-hits",
+            Achievement::Crowd => "crowd",
+            Achievement::BarelyMoved => "barely-moved",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::HundredHits => "Centurion: 100 paddle hits in one game",
+            Achievement::Crowd => "Crowd control: 5 balls kept up for 30 seconds",
+            Achievement::BarelyMoved => "Barely moved: won moving less than half the field",
+        }
+    }
+
+    // whether the player has done what it takes in a field `field_width` wide
+    fn is_met(self, stats: &Stats, field_width: f32) -> bool {
+        match self {
+            Achievement::HundredHits => stats.paddle_hits >= 100,
+            Achievement::Crowd => stats.crowd_time >= Self::CROWD_TIME,
+            Achievement::BarelyMoved => stats.won && stats.paddle_travel <= field_width / 2.0,
+        }
+    }
+}
+
+// the achievements unlocked so far, kept between runs in a file with the id of one on each line,
+// or only in memory without a file
+#[derive(Default)]
+pub struct Achievements {
+    path: Option<PathBuf>,
+    unlocked: BTreeSet<String>,
+}
+
+impl Achievements {
+    // read the unlocked achievements, treating a missing or unreadable file as none
+    pub fn load() -> Achievements {
+        let path = highscore::data_path("achievements");
+        let unlocked = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| text.lines().map(|line| line.trim().to_string()).collect())
+            .unwrap_or_default();
+        Achievements { path, unlocked }
+    }
+
+    // unlock the achievements met by `stats` that weren't already, and get them to announce
+    pub fn check(&mut self, stats: &Stats, field_width: f32) -> Vec<Achievement> {
+        // inserting an achievement that is already unlocked leaves it out
+        Achievement::ALL
+            .into_iter()
+            .filter(|achievement| {
+                achievement.is_met(stats, field_width)
+                    && self.unlocked.insert(achievement.id().to_string())
+            })
+            .collect()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("Failed to create {}: {err}", dir.display()))?;
+        }
+        let text: String = self.unlocked.iter().map(|id| format!("{id}\n")).collect();
+        fs::write(path, text)
+            .map_err(|err| format!("Failed to save achievements to {}: {err}", path.display()))
+    }
+}
@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::daily::Daily;
+use crate::difficulty::Difficulty;
+use crate::game::{ControlMode, Game, Mode};
+use crate::handicap::Handicap;
+use crate::obstacle::Layout;
+use crate::theme::Theme;
+
+// options given on the command line
+pub struct Args {
+    pub mode: Mode,
+    pub control_mode: ControlMode,
+    pub lives: u32,
+    pub target_score: u32,
+    pub width: u32,
+    pub height: u32,
+    pub config: Option<PathBuf>,
+    pub rebound: bool,
+    // whether the game starts in night mode
+    pub night: bool,
+    // whether the game starts with the paths of the balls drawn ahead of them
+    pub practice: bool,
+    // whether the balls are colored by how fast they go instead of each in a color of its own
+    pub speed_colors: bool,
+    // whether the simulation can be paused and stepped through for debugging
+    pub debug: bool,
+    // whether left and right are swapped in the controls, and also in how the field is drawn
+    pub mirror: bool,
+    pub mirror_view: bool,
+    // whether gravity pulls the balls towards a wall that keeps switching
+    pub gravity_shift: bool,
+    // whether there are bonus targets on the top wall
+    pub targets: bool,
+    // whether the versus mode is played as a tournament against computer opponents
+    pub tournament: bool,
+    pub difficulty: Difficulty,
+    pub layout: Layout,
+    pub theme: Theme,
+    pub seed: Option<u64>,
+    // file with a level to play on its own in the campaign mode
+    pub level: Option<PathBuf>,
+    // challenge of the day, which sets the mode, the seed and the mutators
+    pub daily: Option<Daily>,
+    // numbers of balls overriding the ones in the config
+    pub balls: Option<u32>,
+    pub max_balls: Option<u32>,
+    // top speed of the paddles overriding the one in the config
+    pub paddle_velocity: Option<f32>,
+    // seconds between balls thrown in on their own, if they are
+    pub auto_spawn: Option<f32>,
+    // number of fixed steps to simulate without a window, for benchmarking
+    pub headless: Option<u32>,
+    // handicaps of the left and the right player in the versus mode
+    pub left_handicap: Handicap,
+    pub right_handicap: Handicap,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            mode: Mode::Solo,
+            control_mode: ControlMode::Keyboard,
+            lives: Game::DEFAULT_LIVES,
+            target_score: Game::DEFAULT_TARGET_SCORE,
+            width: Game::DEFAULT_WINDOW_WIDTH,
+            height: Game::DEFAULT_WINDOW_HEIGHT,
+            config: None,
+            rebound: false,
+            night: false,
+            practice: false,
+            speed_colors: false,
+            debug: false,
+            mirror: false,
+            mirror_view: false,
+            gravity_shift: false,
+            targets: false,
+            tournament: false,
+            difficulty: Difficulty::Normal,
+            layout: Layout::Empty,
+            theme: Theme::BLUE,
+            seed: None,
+            level: None,
+            daily: None,
+            balls: None,
+            max_balls: None,
+            paddle_velocity: None,
+            auto_spawn: None,
+            headless: None,
+            left_handicap: Handicap::NONE,
+            right_handicap: Handicap::NONE,
+        }
+    }
+}
+
+impl Args {
+    // parse the arguments following the program name
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--versus" => parsed.mode = Mode::Versus,
+                "--computer" => parsed.mode = Mode::Computer,
+                "--two-player" => parsed.mode = Mode::TwoPlayer,
+                "--breakout" => parsed.mode = Mode::Breakout,
+                "--survival" => parsed.mode = Mode::Survival,
+                "--juggling" => parsed.mode = Mode::Juggling,
+                "--time-attack" => parsed.mode = Mode::TimeAttack,
+                "--endless" => parsed.mode = Mode::Endless,
+                "--campaign" => parsed.mode = Mode::Campaign,
+                "--four-player" => parsed.mode = Mode::FourPlayer,
+                "--coop" => parsed.mode = Mode::Coop,
+                "--boss" => parsed.mode = Mode::Boss,
+                "--mouse" => parsed.control_mode = ControlMode::Mouse,
+                "--rebound" => parsed.rebound = true,
+                "--night" => parsed.night = true,
+                "--practice" => parsed.practice = true,
+                "--speed-colors" => parsed.speed_colors = true,
+                "--debug" => parsed.debug = true,
+                "--mirror" => parsed.mirror = true,
+                "--mirror-view" => parsed.mirror_view = true,
+                "--gravity-shift" => parsed.gravity_shift = true,
+                "--targets" => parsed.targets = true,
+                "--tournament" => parsed.tournament = true,
+                "--lives" => parsed.lives = Self::parse_positive(&arg, args.next())?,
+                "--target-score" => parsed.target_score = Self::parse_positive(&arg, args.next())?,
+                "--width" => parsed.width = Self::parse_value(&arg, args.next())?,
+                "--height" => parsed.height = Self::parse_value(&arg, args.next())?,
+                "--difficulty" => parsed.difficulty = Self::parse_value(&arg, args.next())?,
+                "--layout" => parsed.layout = Self::parse_value(&arg, args.next())?,
+                "--theme" => parsed.theme = Self::parse_value(&arg, args.next())?,
+                "--balls" => parsed.balls = Some(Self::parse_positive(&arg, args.next())?),
+                "--max-balls" => parsed.max_balls = Some(Self::parse_positive(&arg, args.next())?),
+                "--paddle-velocity" => {
+                    parsed.paddle_velocity = Some(Self::parse_value(&arg, args.next())?)
+                }
+                "--left-handicap" => parsed.left_handicap = Self::parse_value(&arg, args.next())?,
+                "--right-handicap" => parsed.right_handicap = Self::parse_value(&arg, args.next())?,
+                "--auto-spawn" => parsed.auto_spawn = Some(Self::parse_value(&arg, args.next())?),
+                "--headless" => parsed.headless = Some(Self::parse_positive(&arg, args.next())?),
+                "--seed" => parsed.seed = Some(Self::parse_value(&arg, args.next())?),
+                "--daily" => parsed.daily = Some(Daily::today()),
+                "--level" => parsed.level = Some(Self::parse_value(&arg, args.next())?),
+                "--config" => parsed.config = Some(Self::parse_value(&arg, args.next())?),
+                _ => return Err(format!("Unknown argument: {arg}")),
+            }
+        }
+        // a level from a file is played as a campaign of its own
+        if parsed.level.is_some() {
+            parsed.mode = Mode::Campaign;
+        }
+        // and a tournament is a series of versus matches
+        if parsed.tournament {
+            parsed.mode = Mode::Versus;
+        }
+
+        Ok(parsed)
+    }
+
+    // parse the value given right after an option, e.g. `--width 800`
+    fn parse_value<T: FromStr>(name: &str, value: Option<String>) -> Result<T, String> {
+        let value = value.ok_or_else(|| format!("Missing value for {name}"))?;
+        value
+            .parse()
+            .map_err(|_| format!("Invalid value for {name}: {value}"))
+    }
+
+    fn parse_positive(name: &str, value: Option<String>) -> Result<u32, String> {
+        match Self::parse_value(name, value)? {
+            0 => Err(format!("{name} must be at least 1")),
+            value => Ok(value),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use rand::Rng;
+use sdl2::pixels::Color;
+use std::collections::VecDeque;
+
+use crate::vector::Vector2;
+
+// what a ball is made of, which decides how fast it goes and how much it gets pushed around
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BallKind {
+    Normal,
+    // slower, and not pushed around by spin, the gravity well or the wind at all
+    Heavy,
+    // faster, and pushed around more than a normal ball
+    Light,
+}
+
+impl BallKind {
+    pub fn random(rng: &mut impl Rng) -> BallKind {
+        match rng.gen_range(0..3) {
+            0 => BallKind::Normal,
+            1 => BallKind::Heavy,
+            _ => BallKind::Light,
+        }
+    }
+
+    // how many times as fast as a normal ball it goes
+    pub fn speed(self) -> f32 {
+        match self {
+            BallKind::Normal => 1.0,
+            BallKind::Heavy => 0.8,
+            BallKind::Light => 1.25,
+        }
+    }
+
+    // how many times as much as a normal ball it is pushed around
+    pub fn drift(self) -> f32 {
+        match self {
+            BallKind::Normal => 1.0,
+            BallKind::Heavy => 0.0,
+            BallKind::Light => 1.5,
+        }
+    }
+
+    pub fn mass(self) -> f32 {
+        match self {
+            BallKind::Normal => 1.0,
+            BallKind::Heavy => 3.0,
+            BallKind::Light => 0.5,
+        }
+    }
+
+    // get the color of a ball of this kind, where `normal` is the one of the normal kind
+    pub fn color(self, normal: Color) -> Color {
+        match self {
+            BallKind::Normal => normal,
+            BallKind::Heavy => Color::RGB(80, 80, 100),
+            BallKind::Light => Color::RGB(255, 240, 150),
+        }
+    }
+}
+
+pub struct Ball {
+    pub pos: Vector2,
+    pub vel: Vector2,
+    pub kind: BallKind,
+    // given when the ball is put into play, so that each ball can be told apart
+    pub color: Color,
+    // offset from the center of the player's paddle while the ball sits on it waiting to be
+    // served, or None once it is moving freely
+    pub attached: Option<f32>,
+    // whether the ball was caught by the sticky paddle rather than waiting to be served
+    pub stuck: bool,
+    // how many times faster than when it was served the ball goes after all its paddle hits
+    pub speed_scale: f32,
+    // seconds until the ball can go through a portal again, so that it doesn't keep going back
+    // and forth between two portals next to each other
+    pub portal_cooldown: f32,
+    // sideways acceleration turning the path of the ball counterclockwise, or clockwise if
+    // negative
+    pub spin: f32,
+    // where the ball was over the last few updates, newest first, to draw its trail
+    pub trail: VecDeque<Vector2>,
+}
+
+impl Ball {
+    // a normal ball at `pos` going at `vel`, for tests to set up what they need
+    #[cfg(test)]
+    pub fn at(pos: Vector2, vel: Vector2) -> Ball {
+        Ball {
+            pos,
+            vel,
+            kind: BallKind::Normal,
+            color: Color::RGB(255, 255, 255),
+            attached: None,
+            stuck: false,
+            speed_scale: 1.0,
+            portal_cooldown: 0.0,
+            spin: 0.0,
+            trail: VecDeque::new(),
+        }
+    }
+
+    // how much of its spin a ball loses every second, and keeps bouncing off a wall
+    const SPIN_DECAY: f32 = 1.5;
+    const WALL_SPIN_RETAIN: f32 = 0.5;
+    // how many past positions the trail of a ball is drawn at
+    pub const TRAIL_LENGTH: usize = 8;
+
+    // speed the ball up for another paddle hit, but no further than `max_scale` times the serve
+    // speed, and get how fast it goes now, given the serve speed of a normal ball
+    pub fn hit_speed(&mut self, serve_speed: f32, growth: f32, max_scale: f32) -> f32 {
+        self.speed_scale = (self.speed_scale * growth).min(max_scale);
+        serve_speed * self.kind.speed() * self.speed_scale
+    }
+
+    // pick up spin from a paddle moving at `paddle_vel` as it hits the ball, so that the ball
+    // curves the way the paddle was going
+    pub fn take_spin(&mut self, paddle_vel: Vector2, coefficient: f32) {
+        self.spin = coefficient * self.kind.drift() * paddle_vel.dot(self.side());
+    }
+
+    // curve the path of the ball by its spin for `delta_time`, with the spin wearing off
+    pub fn curve(&mut self, delta_time: f32) {
+        self.vel += self.side() * (self.spin * delta_time);
+        self.spin *= (-Self::SPIN_DECAY * delta_time).exp();
+    }
+
+    pub fn damp_spin(&mut self) {
+        self.spin *= Self::WALL_SPIN_RETAIN;
+    }
+
+    // get the direction to the left of where the ball is going, as seen on the screen
+    fn side(&self) -> Vector2 {
+        self.vel.normalize().rotate(std::f32::consts::FRAC_PI_2)
+    }
+
+    // remember where the ball is now for its trail, forgetting the oldest position once the
+    // trail is full
+    pub fn record_trail(&mut self) {
+        self.trail.truncate(Self::TRAIL_LENGTH - 1);
+        self.trail.push_front(self.pos);
+    }
+
+    // keep a moving ball from going too fast, or from bouncing almost straight up and down
+    // forever
+    pub fn clamp_speed(&mut self, min_speed_x: f32, max_speed: f32) {
+        if self.attached.is_some() {
+            return;
+        }
+        if self.vel.x.abs() < min_speed_x {
+            self.vel.x = min_speed_x.copysign(self.vel.x);
+        }
+        let speed = self.vel.length();
+        if speed > max_speed {
+            self.vel = self.vel * (max_speed / speed);
+        }
+    }
+}
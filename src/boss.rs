@@ -0,0 +1,121 @@
+use rand::Rng;
+use sdl2::rect::Rect;
+
+use crate::obstacle::Obstacle;
+use crate::vector::Vector2;
+
+// a large block at the top of the field that loses a hit point every time a ball hits it, and
+// keeps dropping hazards on the player
+pub struct Boss {
+    pub block: Obstacle,
+    pub hp: u32,
+    // seconds until the boss drops the next hazard
+    pub attack_timer: f32,
+    // seconds left of the flash after a hit
+    pub hit_flash: f32,
+}
+
+// a block falling from the boss that shrinks the paddle for a while if it touches it
+pub struct Hazard {
+    // top left corner
+    pub pos: Vector2,
+    pub size: Vector2,
+}
+
+impl Boss {
+    pub const MAX_HP: u32 = 20;
+    pub const HIT_FLASH_TIME: f32 = 0.15;
+    const ATTACK_INTERVAL: f32 = 2.5;
+    // share of the width of the field the boss takes up, and its height in wall thicknesses
+    const WIDTH_SCALE: f32 = 0.3;
+    const HEIGHT_SCALE: f32 = 3.0;
+    // how far below the top edge the boss sits in wall thicknesses, leaving room above it for
+    // the balls and its health bar
+    const TOP: f32 = 6.0;
+
+    pub fn new(width: f32, thickness: f32) -> Boss {
+        Boss {
+            block: Self::block(width, thickness),
+            hp: Self::MAX_HP,
+            attack_timer: Self::ATTACK_INTERVAL,
+            hit_flash: 0.0,
+        }
+    }
+
+    // get the block of the boss in a field `width` wide
+    pub fn block(width: f32, thickness: f32) -> Obstacle {
+        let size = Vector2::new(width * Self::WIDTH_SCALE, thickness * Self::HEIGHT_SCALE);
+        Obstacle {
+            pos: Vector2::new((width - size.x) / 2.0, thickness * Self::TOP),
+            size,
+        }
+    }
+
+    pub fn is_defeated(&self) -> bool {
+        self.hp == 0
+    }
+
+    // bounce a ball off the boss like off an obstacle, taking a hit point off it if it did
+    pub fn collide(
+        &mut self,
+        ball_pos: &mut Vector2,
+        ball_vel: &mut Vector2,
+        ball_size: f32,
+    ) -> bool {
+        if self.is_defeated() || !self.block.collide(ball_pos, ball_vel, ball_size) {
+            return false;
+        }
+        self.hp -= 1;
+        self.hit_flash = Self::HIT_FLASH_TIME;
+        true
+    }
+
+    // count down to the next attack, and get the hazard dropped from under a random spot of the
+    // boss once it is time for it
+    pub fn update(
+        &mut self,
+        delta_time: f32,
+        thickness: f32,
+        rng: &mut impl Rng,
+    ) -> Option<Hazard> {
+        self.hit_flash = (self.hit_flash - delta_time).max(0.0);
+        self.attack_timer -= delta_time;
+        if self.attack_timer > 0.0 || self.is_defeated() {
+            return None;
+        }
+        self.attack_timer += Self::ATTACK_INTERVAL;
+
+        let size = Vector2::new(thickness * 2.0, thickness);
+        let x = rng.gen_range(self.block.pos.x..=self.block.pos.x + self.block.size.x - size.x);
+        Some(Hazard {
+            pos: Vector2::new(x, self.block.pos.y + self.block.size.y),
+            size,
+        })
+    }
+}
+
+impl Hazard {
+    // how fast hazards fall, in pixels per second
+    const SPEED: f32 = 150.0;
+
+    pub fn fall(&mut self, delta_time: f32) {
+        self.pos.y += Self::SPEED * delta_time;
+    }
+
+    // whether the hazard overlaps a paddle with its collision box at `rect`
+    pub fn touches(&self, rect: Rect) -> bool {
+        self.pos.x < rect.right() as f32
+            && rect.left() as f32 <= self.pos.x + self.size.x
+            && self.pos.y < rect.bottom() as f32
+            && rect.top() as f32 <= self.pos.y + self.size.y
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(
+            self.pos.x as i32,
+            self.pos.y as i32,
+            self.size.x as u32,
+            self.size.y as u32,
+        )
+    }
+}
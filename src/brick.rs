@@ -0,0 +1,52 @@
+use sdl2::pixels::Color;
+
+use crate::obstacle::Obstacle;
+
+// a block in the breakout mode that breaks after being hit as many times as it has hit points
+pub struct Brick {
+    pub block: Obstacle,
+    pub hp: u32,
+}
+
+impl Brick {
+    pub const MAX_HP: u32 = 3;
+
+    // get the color of the brick, darker the more hits it can still take
+    pub fn color(&self) -> Color {
+        match self.hp {
+            1 => Color::RGB(250, 220, 120),
+            2 => Color::RGB(240, 150, 60),
+            _ => Color::RGB(210, 70, 60),
+        }
+    }
+
+    // lay out a grid of bricks in the upper third of a field of the given size, with more rows
+    // and columns, and tougher bricks, the further the player has got
+    pub fn layout(level: u32, width: f32, height: f32, thickness: f32) -> Vec<Brick> {
+        let rows = (4 + level).min(8);
+        let columns = (8 + 2 * level).min(16);
+        let gap = 2.0;
+        let left = thickness;
+        let top = height / 9.0;
+        let brick_width = (width - 2.0 * thickness) / columns as f32;
+        let brick_height = (height / 3.0 - top) / rows as f32;
+
+        let mut bricks = Vec::new();
+        for row in 0..rows {
+            // the top rows are the hardest to break
+            let hp = (1 + level + (rows - 1 - row) / 3).min(Self::MAX_HP);
+            for column in 0..columns {
+                bricks.push(Brick {
+                    block: Obstacle::new(
+                        left + brick_width * (column as f32 + 0.5),
+                        top + brick_height * (row as f32 + 0.5),
+                        brick_width - gap,
+                        brick_height - gap,
+                    ),
+                    hp,
+                });
+            }
+        }
+        bricks
+    }
+}
@@ -0,0 +1,236 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use crate::obstacle::{Layout, Obstacle};
+use crate::vector::Vector2;
+use crate::wind::WindZone;
+
+// what has to be done on a level to go on to the next one
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Goal {
+    // last this many seconds
+    Survive(f32),
+    // hit the balls with the paddle this many times
+    Hits(u32),
+}
+
+impl Goal {
+    pub fn is_met(self, time: f32, hits: u32) -> bool {
+        match self {
+            Goal::Survive(seconds) => time >= seconds,
+            Goal::Hits(target) => hits >= target,
+        }
+    }
+
+    // describe how far the player has got towards the goal
+    pub fn progress(self, time: f32, hits: u32) -> String {
+        match self {
+            Goal::Survive(seconds) => format!("Survive {:.0}/{seconds:.0}s", time.min(seconds)),
+            Goal::Hits(target) => format!("Hits {}/{target}", hits.min(target)),
+        }
+    }
+}
+
+// a rectangle given in fractions of the width and the height of the field, so that it keeps its
+// place when the window is resized
+#[derive(Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Area {
+    // top left corner
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Area {
+    pub fn obstacle(self, width: f32, height: f32) -> Obstacle {
+        Obstacle {
+            pos: Vector2::new(self.x * width, self.y * height),
+            size: Vector2::new(self.width * width, self.height * height),
+        }
+    }
+
+    // check that the area has a size and lies within the field, above the strip at the bottom
+    // where the paddle moves
+    fn validate(&self) -> Result<(), String> {
+        if self.width <= 0.0 || self.height <= 0.0 {
+            return Err(String::from("width and height must be positive"));
+        }
+        if self.x < 0.0 || self.y < 0.0 || self.x + self.width > 1.0 {
+            return Err(String::from("must lie within the field"));
+        }
+        if self.y + self.height > 1.0 - PADDLE_AREA {
+            return Err(format!(
+                "must end above the paddle area, at most {} of the way down",
+                1.0 - PADDLE_AREA
+            ));
+        }
+        Ok(())
+    }
+}
+
+// a band where the wind blows, with its acceleration in pixels per second squared
+#[derive(Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Wind {
+    pub area: Area,
+    pub force: [f32; 2],
+}
+
+impl Wind {
+    pub fn zone(self, width: f32, height: f32) -> WindZone {
+        let Obstacle { pos, size } = self.area.obstacle(width, height);
+        WindZone {
+            pos,
+            size,
+            force: Vector2::new(self.force[0], self.force[1]),
+        }
+    }
+}
+
+// a stage of the campaign, played in the solo field
+pub struct Level {
+    pub name: String,
+    pub layout: Layout,
+    // obstacles and wind on top of those of the layout
+    pub obstacles: Vec<Area>,
+    pub winds: Vec<Wind>,
+    // balls on the field at the start of the level
+    pub balls: usize,
+    // how many times the configured speed the balls are served at, and the configured width
+    // the paddle is
+    pub ball_speed_scale: f32,
+    pub paddle_width_scale: f32,
+    pub goal: Goal,
+}
+
+// share of the height of the field at the bottom that is kept clear for the paddle
+const PADDLE_AREA: f32 = 0.1;
+const MAX_BALLS: usize = 20;
+const MIN_SCALE: f32 = 0.25;
+const MAX_SCALE: f32 = 4.0;
+const MAX_WIND_FORCE: f32 = 2000.0;
+
+// a level as it is written in a TOML file, where everything but the goal can be left out
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LevelFile {
+    name: String,
+    obstacles: Vec<Area>,
+    winds: Vec<Wind>,
+    balls: usize,
+    ball_speed_scale: f32,
+    paddle_width_scale: f32,
+    goal: Option<Goal>,
+}
+
+impl Default for LevelFile {
+    fn default() -> Self {
+        LevelFile {
+            name: String::from("Custom"),
+            obstacles: Vec::new(),
+            winds: Vec::new(),
+            balls: 1,
+            ball_speed_scale: 1.0,
+            paddle_width_scale: 1.0,
+            goal: None,
+        }
+    }
+}
+
+impl LevelFile {
+    // check that the values make a playable level, and get its goal
+    fn validate(&self) -> Result<Goal, String> {
+        for (i, area) in self.obstacles.iter().enumerate() {
+            area.validate()
+                .map_err(|err| format!("obstacles[{i}] {err}"))?;
+        }
+        for (i, wind) in self.winds.iter().enumerate() {
+            wind.area
+                .validate()
+                .map_err(|err| format!("winds[{i}].area {err}"))?;
+            let force = Vector2::new(wind.force[0], wind.force[1]);
+            if !force.length().is_finite() || force.length() > MAX_WIND_FORCE {
+                return Err(format!(
+                    "winds[{i}].force must be no stronger than {MAX_WIND_FORCE}"
+                ));
+            }
+        }
+        if !(1..=MAX_BALLS).contains(&self.balls) {
+            return Err(format!("balls must be between 1 and {MAX_BALLS}"));
+        }
+        if !(MIN_SCALE..=MAX_SCALE).contains(&self.ball_speed_scale) {
+            return Err(format!(
+                "ball_speed_scale must be between {MIN_SCALE} and {MAX_SCALE}"
+            ));
+        }
+        if !(MIN_SCALE..=MAX_SCALE).contains(&self.paddle_width_scale) {
+            return Err(format!(
+                "paddle_width_scale must be between {MIN_SCALE} and {MAX_SCALE}"
+            ));
+        }
+        match self.goal {
+            None => Err(String::from("goal is missing")),
+            Some(Goal::Survive(seconds)) if seconds <= 0.0 || !seconds.is_finite() => Err(
+                String::from("goal.survive must be a positive number of seconds"),
+            ),
+            Some(Goal::Hits(0)) => Err(String::from("goal.hits must be at least 1")),
+            Some(goal) => Ok(goal),
+        }
+    }
+}
+
+impl Level {
+    // read a level from a TOML file, rejecting values that would make it unplayable
+    pub fn load(path: &Path) -> Result<Level, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read level file {}: {err}", path.display()))?;
+        let file: LevelFile = toml::from_str(&text)
+            .map_err(|err| format!("Failed to parse level file {}: {err}", path.display()))?;
+        let goal = file
+            .validate()
+            .map_err(|err| format!("Invalid level file {}: {err}", path.display()))?;
+
+        Ok(Level {
+            name: file.name,
+            layout: Layout::Empty,
+            obstacles: file.obstacles,
+            winds: file.winds,
+            balls: file.balls,
+            ball_speed_scale: file.ball_speed_scale,
+            paddle_width_scale: file.paddle_width_scale,
+            goal,
+        })
+    }
+
+    fn builtin(
+        name: &str,
+        layout: Layout,
+        balls: usize,
+        ball_speed_scale: f32,
+        goal: Goal,
+    ) -> Level {
+        Level {
+            name: name.to_string(),
+            layout,
+            obstacles: Vec::new(),
+            winds: Vec::new(),
+            balls,
+            ball_speed_scale,
+            paddle_width_scale: 1.0,
+            goal,
+        }
+    }
+}
+
+// get the levels of the campaign that comes with the game, played in this order
+pub fn levels() -> Vec<Level> {
+    vec![
+        Level::builtin("Warm-up", Layout::Empty, 1, 1.0, Goal::Hits(15)),
+        Level::builtin("Pillars", Layout::Pillars, 2, 1.1, Goal::Survive(45.0)),
+        Level::builtin("Spinner", Layout::Spinner, 3, 1.2, Goal::Hits(40)),
+    ]
+}
@@ -0,0 +1,114 @@
+use serde::Deserialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+// gameplay constants that can be tuned from a TOML file without recompiling
+#[derive(Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    // size of the walls, the balls and the short side of the paddles
+    pub thickness: f32,
+    // long side of the paddles
+    pub paddle_width: f32,
+    // top speed of the paddles, how quickly they get up to it, and how quickly they stop once
+    // let go, in pixels per second and per second squared
+    pub paddle_velocity: f32,
+    pub paddle_acceleration: f32,
+    pub paddle_friction: f32,
+    // number of balls on the field at the start of a game, and the maximum at the same time
+    pub initial_balls: usize,
+    pub max_balls: usize,
+    // speed of a ball when it is put into play
+    pub ball_speed: f32,
+    // how many times its serve speed a ball can speed up to over a rally, where 1.0 keeps it from
+    // speeding up at all
+    pub max_speed_scale: f32,
+    // how fast the spinner obstacle turns, in degrees per second
+    pub spinner_speed: f32,
+    // how much of the paddle's speed a ball hit while the paddle moves turns into sideways
+    // acceleration that curves its path, where 0.0 turns curve balls off
+    pub spin: f32,
+    // how far the light around each ball and paddle reaches in night mode
+    pub night_light_radius: f32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            thickness: 15.0,
+            paddle_width: 90.0,
+            paddle_velocity: 800.0,
+            paddle_acceleration: 5000.0,
+            paddle_friction: 6000.0,
+            initial_balls: 2,
+            max_balls: 5,
+            ball_speed: 450.0,
+            max_speed_scale: 2.0,
+            spinner_speed: 60.0,
+            spin: 0.4,
+            night_light_radius: 120.0,
+        }
+    }
+}
+
+impl GameConfig {
+    // read the config from a file, falling back to the defaults if it doesn't exist
+    pub fn load(path: &Path) -> Result<GameConfig, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(GameConfig::default()),
+            Err(err) => {
+                return Err(format!(
+                    "Failed to read config file {}: {err}",
+                    path.display()
+                ))
+            }
+        };
+
+        let config: GameConfig = toml::from_str(&text)
+            .map_err(|err| format!("Failed to parse config file {}: {err}", path.display()))?;
+        config
+            .validate()
+            .map_err(|err| format!("Invalid config file {}: {err}", path.display()))?;
+
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.thickness <= 0.0 {
+            return Err(String::from("thickness must be positive"));
+        }
+        if self.paddle_width <= 0.0 {
+            return Err(String::from("paddle_width must be positive"));
+        }
+        if self.paddle_velocity <= 0.0 {
+            return Err(String::from("paddle_velocity must be positive"));
+        }
+        if self.paddle_acceleration <= 0.0 {
+            return Err(String::from("paddle_acceleration must be positive"));
+        }
+        if self.paddle_friction <= 0.0 {
+            return Err(String::from("paddle_friction must be positive"));
+        }
+        if self.initial_balls == 0 {
+            return Err(String::from("initial_balls must be at least 1"));
+        }
+        if self.max_balls < self.initial_balls {
+            return Err(String::from("max_balls must be at least initial_balls"));
+        }
+        if self.ball_speed <= 0.0 {
+            return Err(String::from("ball_speed must be positive"));
+        }
+        if self.max_speed_scale < 1.0 {
+            return Err(String::from("max_speed_scale must be at least 1.0"));
+        }
+        if self.spin < 0.0 {
+            return Err(String::from("spin must not be negative"));
+        }
+        if self.night_light_radius <= 0.0 {
+            return Err(String::from("night_light_radius must be positive"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::obstacle::Layout;
+
+// the challenge of a day, which is the same for everyone playing on that date since the seed of
+// the game and its mutators all come from the date
+#[derive(Clone)]
+pub struct Daily {
+    // the date in UTC as `YYYY-MM-DD`, which the scores of the day are kept under
+    pub date: String,
+    pub seed: u64,
+}
+
+impl Daily {
+    const LAYOUTS: [Layout; 7] = [
+        Layout::Empty,
+        Layout::Blocks,
+        Layout::Pillars,
+        Layout::Spinner,
+        Layout::Portals,
+        Layout::Well,
+        Layout::Wind,
+    ];
+
+    pub fn today() -> Daily {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self::from_days(secs / 86400)
+    }
+
+    // get the challenge of the day `days` days after 1970-01-01
+    fn from_days(days: u64) -> Daily {
+        // convert to a civil date, counting in eras of 400 years starting on 0000-03-01
+        let days = days + 719468;
+        let era = days / 146097;
+        let day_of_era = days % 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+        Daily {
+            date: format!("{year:04}-{month:02}-{day:02}"),
+            seed: year * 10000 + month * 100 + day,
+        }
+    }
+
+    // pick the layout and the mutators of the day with a generator of their own, so that the
+    // game's one starts from the seed all the same
+    pub fn layout(&self) -> Layout {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        Self::LAYOUTS[rng.gen_range(0..Self::LAYOUTS.len())]
+    }
+
+    pub fn gravity_shift(&self) -> bool {
+        StdRng::seed_from_u64(self.seed.wrapping_add(1)).gen_bool(0.3)
+    }
+
+    pub fn targets(&self) -> bool {
+        StdRng::seed_from_u64(self.seed.wrapping_add(2)).gen_bool(0.5)
+    }
+}
@@ -0,0 +1,77 @@
+use std::str::FromStr;
+
+use crate::config::GameConfig;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+// how a difficulty changes the gameplay constants from the config
+pub struct DifficultyParams {
+    pub ball_speed_scale: f32,
+    // how much faster a ball gets on every paddle hit
+    pub speed_growth: f32,
+    pub paddle_width_scale: f32,
+    pub paddle_velocity_scale: f32,
+}
+
+impl Difficulty {
+    pub fn params(self) -> DifficultyParams {
+        match self {
+            Difficulty::Easy => DifficultyParams {
+                ball_speed_scale: 0.8,
+                speed_growth: 1.02,
+                paddle_width_scale: 1.3,
+                paddle_velocity_scale: 1.1,
+            },
+            Difficulty::Normal => DifficultyParams {
+                ball_speed_scale: 1.0,
+                speed_growth: 1.04,
+                paddle_width_scale: 1.0,
+                paddle_velocity_scale: 1.0,
+            },
+            Difficulty::Hard => DifficultyParams {
+                ball_speed_scale: 1.25,
+                speed_growth: 1.06,
+                paddle_width_scale: 0.75,
+                paddle_velocity_scale: 0.9,
+            },
+        }
+    }
+
+    // get the config to play with at this difficulty, leaving the speed growth to be taken from
+    // the params as it isn't part of the config
+    pub fn apply(self, config: &GameConfig) -> GameConfig {
+        let params = self.params();
+        GameConfig {
+            ball_speed: config.ball_speed * params.ball_speed_scale,
+            paddle_width: config.paddle_width * params.paddle_width_scale,
+            paddle_velocity: config.paddle_velocity * params.paddle_velocity_scale,
+            ..*config
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("Unknown difficulty: {s}")),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+// ways setting up a game can fail, with the error messages of SDL2 and of reading files kept as
+// they are
+#[derive(Debug)]
+pub enum GameError {
+    // the options or the config make a game that can't be played
+    InvalidOptions(String),
+    WindowTooSmall {
+        width: u32,
+        height: u32,
+        min_size: u32,
+    },
+    // the config file or the level file couldn't be read, parsed or validated, with the message
+    // saying which
+    ConfigParse(String),
+    LevelParse(String),
+    SdlInit(String),
+    Video(String),
+    WindowCreate(String),
+    WindowMinimumSize(String),
+    CanvasCreate(String),
+    EventPump(String),
+    TtfInit(String),
+    FontLoad {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::InvalidOptions(err) => write!(f, "Invalid options: {err}"),
+            GameError::WindowTooSmall {
+                width,
+                height,
+                min_size,
+            } => write!(
+                f,
+                "Window size {width}x{height} is too small, it must be at least \
+                 {min_size}x{min_size}"
+            ),
+            GameError::ConfigParse(err) | GameError::LevelParse(err) => write!(f, "{err}"),
+            GameError::SdlInit(err) => write!(f, "Failed to initialize SDL2: {err}"),
+            GameError::Video(err) => {
+                write!(f, "Failed to initialize SDL2 video subsystem: {err}")
+            }
+            GameError::WindowCreate(err) => write!(f, "Failed to create window: {err}"),
+            GameError::WindowMinimumSize(err) => {
+                write!(f, "Failed to set minimum window size: {err}")
+            }
+            GameError::CanvasCreate(err) => write!(f, "Failed to create canvas: {err}"),
+            GameError::EventPump(err) => write!(f, "Failed to get SDL2 event pump: {err}"),
+            GameError::TtfInit(err) => write!(f, "Failed to initialize SDL2 TTF context: {err}"),
+            GameError::FontLoad { path, message } => {
+                write!(f, "Failed to load font {}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for GameError {}
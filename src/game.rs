@@ -1,262 +1,4337 @@
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::achievement::{Achievement, Achievements, Stats};
+use crate::args::Args;
+use crate::ball::{Ball, BallKind};
+use crate::boss::{Boss, Hazard};
+use crate::brick::Brick;
+use crate::campaign::{self, Level};
+use crate::config::GameConfig;
+use crate::daily::Daily;
+use crate::difficulty::Difficulty;
+use crate::error::GameError;
+use crate::gamepad::Gamepad;
+use crate::gravity::GravityShift;
+use crate::handicap::Handicap;
+use crate::highscore::HighScores;
+use crate::obstacle::{Layout, Obstacle, Spinner};
+use crate::paddle::Paddle;
+use crate::physics::{self, Bounce, Bounds, Contacts, Guard};
+use crate::portal::PortalPair;
+use crate::sound::{Sound, Sounds};
+use crate::target::Target;
+use crate::theme::{self, Theme};
+use crate::tournament::Tournament;
+use crate::vector::Vector2;
+use crate::well::GravityWell;
+use crate::wind::WindZone;
+use std::cmp::Ordering;
 use std::collections::VecDeque;
-use std::process;
-use std::time::Instant;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use sdl2::event::Event;
+use sdl2::controller::Button;
+use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::{Keycode, Scancode};
 use sdl2::pixels::Color;
-use sdl2::rect::Rect;
-use sdl2::render::Canvas;
-use sdl2::video::Window;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::ttf::{Font, Sdl2TtfContext};
+use sdl2::video::{FullscreenType, Window};
+use sdl2::EventPump;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    // one paddle at the bottom, bouncing balls against the walls
+    Solo,
+    // two players with paddles on the left and right, scoring on each other's goal
+    Versus,
+    // one paddle at the bottom against a computer-controlled paddle at the top
+    Computer,
+    // two players with paddles at the bottom and the top, scoring past each other's paddle
+    TwoPlayer,
+    // one paddle at the bottom, breaking the bricks at the top with the balls
+    Breakout,
+    // one paddle at the bottom that keeps shrinking, lasting as long as possible on one life
+    Survival,
+    // one paddle at the bottom keeping more and more balls in the air, until it drops one
+    Juggling,
+    // one paddle at the bottom scoring as many paddle hits as possible before the time runs out
+    TimeAttack,
+    // one paddle at the bottom scoring paddle hits while balls drop in faster and faster, until
+    // three of them have got past it
+    Endless,
+    // one paddle at the bottom playing through a series of levels, each with a goal to meet
+    Campaign,
+    // four players with a paddle on every side, each losing a life when a ball gets past them,
+    // until only one is left
+    FourPlayer,
+    // two players side by side at the bottom, each on their own half, keeping the balls of the
+    // endless mode up together
+    Coop,
+    // one paddle at the bottom wearing down a boss at the top with the balls, while dodging the
+    // hazards it drops
+    Boss,
+}
+
+// how the player at the bottom moves their paddle
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ControlMode {
+    Keyboard,
+    // the paddle follows the mouse cursor
+    Mouse,
+}
+
+impl Mode {
+    // get the name the best score of the mode is kept under, if the player has a score of their
+    // own in it
+    fn high_score_name(self) -> Option<&'static str> {
+        match self {
+            Mode::Solo => Some("solo"),
+            Mode::Computer => Some("computer"),
+            Mode::Breakout => Some("breakout"),
+            Mode::Survival => Some("survival"),
+            Mode::Juggling => Some("juggling"),
+            Mode::TimeAttack => Some("time-attack"),
+            Mode::Endless => Some("endless"),
+            Mode::Coop => Some("coop"),
+            Mode::Campaign => Some("campaign"),
+            Mode::Boss => Some("boss"),
+            Mode::Versus | Mode::TwoPlayer | Mode::FourPlayer => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum GameState {
+    // the menu shown before the game, with the option picked in it
+    Menu(MenuItem),
+    // the seconds left before play starts, with the balls already in place
+    Countdown(f32),
+    Playing,
+    GameOver,
+    // someone has reached the target score
+    Won(Winner),
+    // the player has won a match of the tournament, and the next one is about to be played
+    Bracket,
+    // the time has run out in the time attack mode
+    TimeUp,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MenuItem {
+    Start,
+    Quit,
+}
+
+impl MenuItem {
+    const ALL: [MenuItem; 2] = [MenuItem::Start, MenuItem::Quit];
+
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::Start => "Start",
+            MenuItem::Quit => "Quit",
+        }
+    }
+
+    // get the option `step` places further down the menu, wrapping around at either end
+    fn moved(self, step: isize) -> MenuItem {
+        let count = Self::ALL.len() as isize;
+        Self::ALL[(self as isize + step).rem_euclid(count) as usize]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Winner {
+    Player,
+    Computer,
+    LeftPlayer,
+    RightPlayer,
+    TopPlayer,
+    BottomPlayer,
+}
+
+impl Winner {
+    fn message(self) -> &'static str {
+        match self {
+            Winner::Player => "You win!",
+            Winner::Computer => "The computer wins!",
+            Winner::LeftPlayer => "The left player wins!",
+            Winner::RightPlayer => "The right player wins!",
+            Winner::TopPlayer => "The top player wins!",
+            Winner::BottomPlayer => "The bottom player wins!",
+        }
+    }
+}
+
+// the sides of the field, each defended by a player in the four-player mode
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Bottom,
+    Top,
+    Left,
+    Right,
+}
+
+impl Side {
+    const ALL: [Side; 4] = [Side::Bottom, Side::Top, Side::Left, Side::Right];
+
+    fn winner(self) -> Winner {
+        match self {
+            Side::Bottom => Winner::BottomPlayer,
+            Side::Top => Winner::TopPlayer,
+            Side::Left => Winner::LeftPlayer,
+            Side::Right => Winner::RightPlayer,
+        }
+    }
 
-struct Vector2 {
-    x: f32,
-    y: f32,
+    // color of the player's paddle and lives, so that everyone can tell which side is theirs
+    fn color(self) -> Color {
+        match self {
+            Side::Bottom => Color::RGB(255, 255, 255),
+            Side::Top => Color::RGB(240, 200, 60),
+            Side::Left => Color::RGB(230, 80, 80),
+            Side::Right => Color::RGB(120, 230, 120),
+        }
+    }
+
+    // direction pointing from the side into the field
+    fn inward(self) -> Vector2 {
+        match self {
+            Side::Bottom => Vector2::new(0.0, -1.0),
+            Side::Top => Vector2::new(0.0, 1.0),
+            Side::Left => Vector2::new(1.0, 0.0),
+            Side::Right => Vector2::new(-1.0, 0.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PowerUpKind {
+    Grow,
+    Shrink,
+    Multiball,
+    SlowMotion,
+    Sticky,
+    Shield,
+}
+
+impl PowerUpKind {
+    // pick a kind of power-up, with the ones that make the game harder being rarer
+    fn random(rng: &mut impl Rng) -> PowerUpKind {
+        match rng.gen_range(0..10) {
+            0..=1 => PowerUpKind::Grow,
+            2..=3 => PowerUpKind::Multiball,
+            4..=5 => PowerUpKind::SlowMotion,
+            6 => PowerUpKind::Sticky,
+            7 => PowerUpKind::Shield,
+            _ => PowerUpKind::Shrink,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            PowerUpKind::Grow => Color::RGB(120, 230, 120),
+            PowerUpKind::Shrink => Color::RGB(230, 60, 60),
+            PowerUpKind::Multiball => Color::RGB(240, 200, 60),
+            PowerUpKind::SlowMotion => Color::RGB(80, 160, 240),
+            PowerUpKind::Sticky => Color::RGB(190, 110, 230),
+            PowerUpKind::Shield => Color::RGB(90, 230, 210),
+        }
+    }
+}
+
+// a capsule falling from the top that has some effect on the game when caught by the player
+struct PowerUp {
+    pos: Vector2,
+    kind: PowerUpKind,
 }
 
-struct Ball {
+// text drifting away from where something happened, such as the points scored there
+struct Popup {
+    text: String,
     pos: Vector2,
-    vel: Vector2,
+    remaining: f32,
+}
+
+// a change in the size of the player's paddle that wears off after a while
+struct PaddleEffect {
+    scale: f32,
+    remaining: f32,
+}
+
+// sets a game up one option at a time on top of the defaults, e.g.
+// `GameBuilder::new().window_size(800, 600).seed(42).build()`
+pub struct GameBuilder {
+    args: Args,
+}
+
+impl GameBuilder {
+    pub fn new() -> GameBuilder {
+        GameBuilder {
+            args: Args::default(),
+        }
+    }
+
+    pub fn from_args(args: &Args) -> GameBuilder {
+        let mut builder = GameBuilder::new()
+            .mode(args.mode)
+            .control_mode(args.control_mode)
+            .lives(args.lives)
+            .target_score(args.target_score)
+            .window_size(args.width, args.height)
+            .rebound(args.rebound)
+            .night(args.night)
+            .practice(args.practice)
+            .speed_colors(args.speed_colors)
+            .debug(args.debug)
+            .mirror(args.mirror, args.mirror_view)
+            .gravity_shift(args.gravity_shift)
+            .targets(args.targets)
+            .tournament(args.tournament)
+            .difficulty(args.difficulty)
+            .layout(args.layout)
+            .theme(args.theme)
+            .handicaps(args.left_handicap, args.right_handicap);
+        if let Some(path) = &args.config {
+            builder = builder.config(path.clone());
+        }
+        if let Some(path) = &args.level {
+            builder = builder.level(path.clone());
+        }
+        if let Some(seed) = args.seed {
+            builder = builder.seed(seed);
+        }
+        if let Some(balls) = args.balls {
+            builder = builder.initial_balls(balls);
+        }
+        if let Some(max_balls) = args.max_balls {
+            builder = builder.max_balls(max_balls);
+        }
+        if let Some(paddle_velocity) = args.paddle_velocity {
+            builder = builder.paddle_velocity(paddle_velocity);
+        }
+        if let Some(interval) = args.auto_spawn {
+            builder = builder.auto_spawn(interval);
+        }
+        if let Some(steps) = args.headless {
+            builder = builder.headless(steps);
+        }
+        // the daily challenge comes last since it overrides the mode, the seed and the mutators
+        if let Some(daily) = &args.daily {
+            builder = builder.daily(daily.clone());
+        }
+        builder
+    }
+
+    pub fn mode(mut self, mode: Mode) -> GameBuilder {
+        self.args.mode = mode;
+        self
+    }
+
+    pub fn control_mode(mut self, control_mode: ControlMode) -> GameBuilder {
+        self.args.control_mode = control_mode;
+        self
+    }
+
+    pub fn lives(mut self, lives: u32) -> GameBuilder {
+        self.args.lives = lives;
+        self
+    }
+
+    pub fn target_score(mut self, target_score: u32) -> GameBuilder {
+        self.args.target_score = target_score;
+        self
+    }
+
+    pub fn window_size(mut self, width: u32, height: u32) -> GameBuilder {
+        self.args.width = width;
+        self.args.height = height;
+        self
+    }
+
+    pub fn rebound(mut self, rebound: bool) -> GameBuilder {
+        self.args.rebound = rebound;
+        self
+    }
+
+    pub fn night(mut self, night: bool) -> GameBuilder {
+        self.args.night = night;
+        self
+    }
+
+    pub fn practice(mut self, practice: bool) -> GameBuilder {
+        self.args.practice = practice;
+        self
+    }
+
+    pub fn speed_colors(mut self, speed_colors: bool) -> GameBuilder {
+        self.args.speed_colors = speed_colors;
+        self
+    }
+
+    pub fn debug(mut self, debug: bool) -> GameBuilder {
+        self.args.debug = debug;
+        self
+    }
+
+    pub fn mirror(mut self, mirror: bool, mirror_view: bool) -> GameBuilder {
+        self.args.mirror = mirror;
+        self.args.mirror_view = mirror_view;
+        self
+    }
+
+    pub fn gravity_shift(mut self, gravity_shift: bool) -> GameBuilder {
+        self.args.gravity_shift = gravity_shift;
+        self
+    }
+
+    pub fn targets(mut self, targets: bool) -> GameBuilder {
+        self.args.targets = targets;
+        self
+    }
+
+    pub fn tournament(mut self, tournament: bool) -> GameBuilder {
+        self.args.tournament = tournament;
+        self
+    }
+
+    pub fn difficulty(mut self, difficulty: Difficulty) -> GameBuilder {
+        self.args.difficulty = difficulty;
+        self
+    }
+
+    pub fn layout(mut self, layout: Layout) -> GameBuilder {
+        self.args.layout = layout;
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> GameBuilder {
+        self.args.theme = theme;
+        self
+    }
+
+    // read the gameplay constants from a TOML file
+    pub fn config(mut self, path: PathBuf) -> GameBuilder {
+        self.args.config = Some(path);
+        self
+    }
+
+    // play a level from a file as a campaign of its own
+    pub fn level(mut self, path: PathBuf) -> GameBuilder {
+        self.args.mode = Mode::Campaign;
+        self.args.level = Some(path);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> GameBuilder {
+        self.args.seed = Some(seed);
+        self
+    }
+
+    pub fn daily(mut self, daily: Daily) -> GameBuilder {
+        self.args.mode = Mode::Solo;
+        self.args.seed = Some(daily.seed);
+        self.args.layout = daily.layout();
+        self.args.gravity_shift = daily.gravity_shift();
+        self.args.targets = daily.targets();
+        self.args.daily = Some(daily);
+        self
+    }
+
+    pub fn headless(mut self, steps: u32) -> GameBuilder {
+        self.args.headless = Some(steps);
+        self
+    }
+
+    pub fn initial_balls(mut self, balls: u32) -> GameBuilder {
+        self.args.balls = Some(balls);
+        self
+    }
+
+    pub fn max_balls(mut self, max_balls: u32) -> GameBuilder {
+        self.args.max_balls = Some(max_balls);
+        self
+    }
+
+    pub fn paddle_velocity(mut self, paddle_velocity: f32) -> GameBuilder {
+        self.args.paddle_velocity = Some(paddle_velocity);
+        self
+    }
+
+    pub fn auto_spawn(mut self, interval: f32) -> GameBuilder {
+        self.args.auto_spawn = Some(interval);
+        self
+    }
+
+    pub fn handicaps(mut self, left: Handicap, right: Handicap) -> GameBuilder {
+        self.args.left_handicap = left;
+        self.args.right_handicap = right;
+        self
+    }
+
+    pub fn build(self) -> Result<Game, GameError> {
+        Game::new(&self.args)
+    }
 }
 
 pub struct Game {
-    sdl_context: sdl2::Sdl,
-    canvas: Canvas<Window>,
+    _sdl_context: sdl2::Sdl,
+    event_pump: EventPump,
+    // where the game is drawn, which a headless game has none of
+    canvas: Option<Canvas<Window>>,
+    // font the text is drawn in, which a headless game has none of either
+    font: Option<Font<'static, 'static>>,
+    // number of steps to simulate without a window instead of playing, if that is what is asked
+    headless_steps: Option<u32>,
     is_running: bool,
-    ticks_count: Instant,
+    is_paused: bool,
+    // when the player last touched the controls, and whether the game is playing itself as a
+    // demo since they have been away for a while
+    last_input: Instant,
+    demo: bool,
+    show_fps: bool,
+    theme: Theme,
+    // how many balls have been given a color from the theme, to give the next one the next color
+    ball_colors: usize,
+    // whether the balls are drawn in colors showing how fast they go instead of their own
+    speed_colors: bool,
+    // whether the velocity of each ball and the collision box of each paddle are drawn
+    debug_draw: bool,
+    // whether Space pauses the simulation so that it can be stepped through, and whether a step
+    // has been asked for that hasn't been taken yet
+    debug: bool,
+    single_step: bool,
+    // whether the field is dark but for the light around the balls and the paddles
+    night: bool,
+    // whether the path each ball is about to take is drawn, for practicing
+    practice: bool,
+    // whether left and right are swapped in the controls, and also in how the field is drawn
+    mirror: bool,
+    mirror_view: bool,
+    // frames per second, averaged over the last few seconds
+    fps: f32,
+    // how hard the screen is shaking after a miss, from 1 right after it down to 0, and how far
+    // the field is drawn off its place for it this frame
+    shake: f32,
+    shake_offset: (i32, i32),
+    // randomness for effects that don't change how the game plays, kept apart from the game's
+    // so that a seeded game plays out the same however fast it is drawn
+    effects_rng: StdRng,
+    config: GameConfig,
+    difficulty: Difficulty,
+    speed_growth: f32,
+    mode: Mode,
+    control_mode: ControlMode,
+    width: f32,
+    height: f32,
+    // None when audio isn't available, so that the game goes on silently
+    sounds: Option<Sounds>,
+    // controller steering the player's paddle alongside the keyboard, if one can be used
+    gamepad: Option<Gamepad>,
+    // source of every random choice in the game, so that a game can be played again from a seed
+    rng: StdRng,
+    layout: Layout,
+    obstacles: Vec<Obstacle>,
+    // bricks left to break in the breakout mode, and how many times they have all been broken
+    bricks: Vec<Brick>,
+    level: u32,
+    // the levels of the campaign, which are only the one in the file given if there is one
+    levels: Vec<Level>,
+    // the level of the campaign being played, how long it has been played for and how many
+    // paddle hits have been made on it
+    campaign_level: usize,
+    level_time: f32,
+    level_hits: u32,
+    // serve speed of the balls before a campaign level scales it
+    base_ball_speed: f32,
+    spinner: Option<Spinner>,
+    portal_pairs: Vec<PortalPair>,
+    well: Option<GravityWell>,
+    winds: Vec<WindZone>,
+    // gravity over the whole field that keeps switching walls, if the mutator is on
+    gravity: Option<GravityShift>,
+    // the boss and the hazards it dropped in the boss mode
+    boss: Option<Boss>,
+    hazards: Vec<Hazard>,
+    // the tournament being played through in the versus mode, if it is
+    tournament: Option<Tournament>,
+    // seconds between balls thrown in on their own, if they are, and since the last one was
+    auto_spawn: Option<f32>,
+    spawn_timer: f32,
+    // whether there are bonus targets on the top wall, and the ones there are
+    bonus_targets: bool,
+    targets: Vec<Target>,
+    popups: Vec<Popup>,
     balls: VecDeque<Ball>,
-    paddle_pos: Vector2,
-    paddle_dir: i32,
+    paddle: Paddle,
+    // the second player's paddle at the bottom in the co-op mode
+    partner_paddle: Paddle,
+    power_ups: Vec<PowerUp>,
+    power_up_timer: f32,
+    paddle_effects: Vec<PaddleEffect>,
+    // width of the paddle before the power-ups change it
+    paddle_base_width: f32,
+    // seconds of slow motion left, counted in real time
+    slow_motion_remaining: f32,
+    // whether the bullet time key is held down
+    bullet_time: bool,
+    sticky_remaining: f32,
+    // whether there is a barrier below the paddle to save one ball, and how much longer to show
+    // it breaking after it did
+    shield: bool,
+    shield_flash: f32,
+    // seconds left of the player's dash, and until the player can dash again
+    dash_remaining: f32,
+    dash_cooldown: f32,
+    left_paddle: Paddle,
+    right_paddle: Paddle,
+    // kept over resets, so that a rematch is played on the same terms
+    left_handicap: Handicap,
+    right_handicap: Handicap,
+    left_score: u32,
+    right_score: u32,
+    // whether a versus match tied at match point is being decided by the next point, with
+    // smaller paddles and a single ball that speeds up faster, and for how many more seconds
+    // this is announced
+    sudden_death: bool,
+    sudden_death_banner: f32,
+    // the computer's paddle, which moves slower than the player's, or the top player's
+    top_paddle: Paddle,
+    // points of the computer or the top player
+    top_score: u32,
+    // whether balls that get past the computer bounce back off the top edge instead of scoring
+    rebound: bool,
+    ai_error_margin: f32,
+    ai_reaction_timer: f32,
+    ai_target_x: f32,
+    score: u32,
+    high_scores: HighScores,
+    // challenge of the day being played, if it is, and the best scores of each day
+    daily: Option<Daily>,
+    daily_scores: HighScores,
+    // what the player has done in this game, the achievements unlocked over all runs, and the
+    // ones just unlocked waiting to be announced, with how much longer the first one is shown
+    stats: Stats,
+    achievements: Achievements,
+    unlocked: VecDeque<Achievement>,
+    unlocked_timer: f32,
+    // seconds the player has lasted in the survival mode, and the longest since starting
+    survival_time: f32,
+    best_survival_time: f32,
+    // points building up in the juggling mode, seconds until the next ball is thrown in, and
+    // the most balls kept in the air at once
+    juggling_points: f32,
+    juggling_timer: f32,
+    peak_balls: usize,
+    // seconds until the next ball drops in in the endless mode, and between the drops, which
+    // get shorter every time
+    endless_timer: f32,
+    endless_interval: f32,
+    // seconds left on the clock in the time attack mode
+    time_remaining: f32,
+    // paddle hits in a row since a ball was last lost, shared by all the balls, and the most of
+    // them over every game played since starting
+    rally: u32,
+    longest_rally: u32,
+    misses: u32,
+    lives: u32,
+    // lives of each player in the four-player mode, indexed by their side
+    side_lives: [u32; 4],
+    initial_lives: u32,
+    target_score: u32,
+    state: GameState,
 }
 
 impl Game {
-    const THICKNESS: f32 = 15.0;
-    const WINDOW_WIDTH: f32 = 1024.0;
-    const WINDOW_HEIGHT: f32 = 768.0;
-    const PADDLE_WIDTH: f32 = 6.0 * Game::THICKNESS;
-    const PADDLE_VEL: f32 = 800.0;
+    pub const DEFAULT_WINDOW_WIDTH: u32 = 1024;
+    pub const DEFAULT_WINDOW_HEIGHT: u32 = 768;
+    const FRAME_TIME: Duration = Duration::from_millis(16);
+    const FPS_SMOOTHING: f32 = 0.05;
+    const UPDATE_STEP: f32 = 1.0 / 120.0;
+    const MAX_FRAME_TIME: f32 = 0.05;
+    pub const DEFAULT_LIVES: u32 = 3;
+    pub const DEFAULT_TARGET_SCORE: u32 = 11;
+    const BALL_MIN_SPEED_X: f32 = 60.0;
+    // how much a moving paddle angles a served ball, as if it hit that far from the center
+    const LAUNCH_OFFSET: f32 = 0.5;
+    const POWER_UP_INTERVAL: f32 = 10.0;
+    // bonus targets on the top wall, how many points a hit on one is worth, and how many random
+    // places are tried for one before waiting to try again
+    const TARGET_COUNT: usize = 2;
+    const TARGET_POINTS: u32 = 5;
+    const TARGET_PLACE_TRIES: u32 = 20;
+    const TARGET_COLOR: Color = Color::RGB(255, 200, 60);
+    const BOSS_COLOR: Color = Color::RGB(200, 40, 60);
+    const HAZARD_COLOR: Color = Color::RGB(255, 110, 40);
+    // bonus for hitting a ball with the middle of the paddle
+    const PERFECT_HIT_POINTS: u32 = 2;
+    // how long popups last and how fast they drift down from where they appear
+    const POPUP_TIME: f32 = 0.8;
+    const POPUP_SPEED: f32 = 40.0;
+    // how long each newly unlocked achievement is announced for
+    const UNLOCKED_TIME: f32 = 3.0;
+    // seconds counted down before play starts
+    const COUNTDOWN_TIME: f32 = 3.0;
+    // how long the controls have to be left alone before the game starts playing itself
+    const ATTRACT_IDLE_TIME: Duration = Duration::from_secs(30);
+    const POWER_UP_SPEED: f32 = 150.0;
+    const SPLIT_ANGLE: f32 = 30.0 * std::f32::consts::PI / 180.0;
+    const PADDLE_EFFECT_TIME: f32 = 15.0;
+    // how long a hazard dropped by the boss shrinks the paddle for, and to what share of its width
+    const HAZARD_EFFECT_TIME: f32 = 4.0;
+    const HAZARD_EFFECT_SCALE: f32 = 0.6;
+    const SLOW_MOTION_TIME: f32 = 5.0;
+    const SLOW_MOTION_SCALE: f32 = 0.5;
+    const SLOW_MOTION_WIND_DOWN: f32 = 0.5;
+    // how fast the world moves while the bullet time key is held
+    const BULLET_TIME_SCALE: f32 = 0.3;
+    // size of the cells the darkness of night mode is drawn in, how dark it gets, and how much
+    // of the light radius it takes for the light to fade out
+    const NIGHT_CELL_SIZE: u32 = 8;
+    const NIGHT_DARKNESS: u8 = 245;
+    const NIGHT_FADE: f32 = 0.4;
+    // how long the screen shakes after a miss, and the most it throws the field off its place by,
+    // in pixels, which keeps the walls from shaking far out of the window
+    const SHAKE_TIME: f32 = 0.4;
+    const SHAKE_MAX_OFFSET: f32 = 6.0;
+    // opacity of the newest part of a ball trail
+    const TRAIL_ALPHA: u8 = 120;
+    // how far ahead the path of each ball is drawn in practice, in seconds, and how opaque it is
+    const PREDICTION_TIME: f32 = 2.0;
+    const PREDICTION_ALPHA: u8 = 90;
+    const STICKY_TIME: f32 = 10.0;
+    const SHIELD_FLASH_TIME: f32 = 0.3;
+    const PORTAL_COOLDOWN: f32 = 0.3;
+    const SURVIVAL_SHRINK_RATE: f32 = 0.01;
+    const SURVIVAL_MIN_WIDTH_SCALE: f32 = 0.3;
+    const JUGGLING_INTERVAL: f32 = 20.0;
+    const TIME_ATTACK_TIME: f32 = 60.0;
+    const ENDLESS_ESCAPES: u32 = 3;
+    const ENDLESS_START_INTERVAL: f32 = 10.0;
+    const ENDLESS_MIN_INTERVAL: f32 = 2.0;
+    const ENDLESS_INTERVAL_SCALE: f32 = 0.9;
+    // seconds before a ball drops in that where it will appear is shown
+    const ENDLESS_TELEGRAPH_TIME: f32 = 1.0;
+    const TIME_ATTACK_PENALTY: f32 = 5.0;
+    const DASH_TIME: f32 = 0.15;
+    const DASH_COOLDOWN: f32 = 2.0;
+    const DASH_SPEED_SCALE: f32 = 3.0;
+    const SUDDEN_DEATH_WIDTH_SCALE: f32 = 0.75;
+    // how much more the balls speed up on every paddle hit during sudden death
+    const SUDDEN_DEATH_EXTRA_GROWTH: f32 = 1.05;
+    const SUDDEN_DEATH_BANNER_TIME: f32 = 3.0;
+    // seconds of travel the velocity line of a ball is drawn for
+    const DEBUG_VELOCITY_SCALE: f32 = 0.2;
+    // fraction of the player's paddle velocity the computer can move at, so that it can be beaten
+    const AI_SPEED_RATIO: f32 = 0.75;
+    const AI_ERROR_MARGIN: f32 = 30.0;
+    const AI_REACTION_TIME: f32 = 0.15;
+    const FONT_FILE: &'static str = "DejaVuSansMono-Bold.ttf";
+    const FONT_SIZE: u16 = 32;
+    const WALL_SOUND_FILE: &'static str = "wall.wav";
+    const PADDLE_SOUND_FILE: &'static str = "paddle.wav";
+
+    // set up a game as the command line asks
+    pub fn build(args: &Args) -> Result<Game, GameError> {
+        GameBuilder::from_args(args).build()
+    }
+
+    fn new(args: &Args) -> Result<Game, GameError> {
+        let config = match &args.config {
+            Some(path) => GameConfig::load(path).map_err(GameError::ConfigParse)?,
+            None => GameConfig::default(),
+        };
+        let mut config = args.difficulty.apply(&config);
+        if let Some(balls) = args.balls {
+            config.initial_balls = balls as usize;
+        }
+        if let Some(max_balls) = args.max_balls {
+            config.max_balls = max_balls as usize;
+        }
+        if let Some(paddle_velocity) = args.paddle_velocity {
+            config.paddle_velocity = paddle_velocity;
+        }
+        config.validate().map_err(GameError::InvalidOptions)?;
+
+        // enough room for the walls at both ends and for the paddle to move at least its own width
+        let min_size = (2.0 * config.paddle_width + 2.0 * config.thickness) as u32;
+        if args.width < min_size || args.height < min_size {
+            return Err(GameError::WindowTooSmall {
+                width: args.width,
+                height: args.height,
+                min_size,
+            });
+        }
+        let width = args.width as f32;
+        let height = args.height as f32;
+
+        if let Some(interval) = args.auto_spawn {
+            if !(interval > 0.0 && interval.is_finite()) {
+                return Err(GameError::InvalidOptions(format!(
+                    "auto spawn interval must be positive, got {interval}"
+                )));
+            }
+        }
+
+        // handicaps only matter when two players face each other from the sides
+        let (left_handicap, right_handicap) = match args.mode {
+            Mode::Versus if !args.tournament => (args.left_handicap, args.right_handicap),
+            _ => (Handicap::NONE, Handicap::NONE),
+        };
+        for handicap in [left_handicap, right_handicap] {
+            if handicap.head_start >= args.target_score {
+                return Err(GameError::InvalidOptions(format!(
+                    "a head start of {} must be less than the target score of {}",
+                    handicap.head_start, args.target_score
+                )));
+            }
+        }
 
-    pub fn build() -> Result<Game, String> {
         let sdl_context = match sdl2::init() {
             Ok(sdl_context) => sdl_context,
-            Err(err) => return Err(format!("Failed to initialize SDL2: {err}")),
+            Err(err) => return Err(GameError::SdlInit(err)),
         };
 
-        let video_subsystem = match sdl_context.video() {
-            Ok(video_subsystem) => video_subsystem,
-            Err(err) => return Err(format!("Failed to initialize SDL2 video subsystem: {err}")),
+        // a headless game is only simulated, so it has neither a window nor sound
+        let canvas = match args.headless {
+            Some(_) => None,
+            None => Some(Self::create_canvas(&sdl_context, args, min_size)?),
         };
 
-        let window = video_subsystem
-            .window(
-                "Pong",
-                Self::WINDOW_WIDTH as u32,
-                Self::WINDOW_HEIGHT as u32,
-            )
-            .position_centered()
-            .build();
-        let window = match window {
-            Ok(window) => window,
-            Err(err) => return Err(format!("Failed to create window: {err}")),
+        let event_pump = match sdl_context.event_pump() {
+            Ok(event_pump) => event_pump,
+            Err(err) => return Err(GameError::EventPump(err)),
         };
 
-        let canvas = match window.into_canvas().build() {
-            Ok(canvas) => canvas,
-            Err(err) => return Err(format!("Failed to create canvas: {err}")),
+        let sounds = if args.headless.is_some() {
+            None
+        } else {
+            match Sounds::load(
+                &sdl_context,
+                &Self::asset_path(Self::WALL_SOUND_FILE),
+                &Self::asset_path(Self::PADDLE_SOUND_FILE),
+            ) {
+                Ok(sounds) => Some(sounds),
+                Err(err) => {
+                    eprintln!("Warning: {err}, playing without sound");
+                    None
+                }
+            }
         };
 
-        let mut balls: VecDeque<Ball> = VecDeque::new();
-        balls.push_front(Ball {
-            pos: Vector2 {
-                x: Self::WINDOW_WIDTH * 3.0 / 4.0,
-                y: Self::WINDOW_HEIGHT / 2.0,
-            },
-            vel: Self::get_random_velocity(),
-        });
-        balls.push_front(Ball {
-            pos: Vector2 {
-                x: Self::WINDOW_WIDTH / 4.0,
-                y: Self::WINDOW_HEIGHT / 2.0,
-            },
-            vel: Self::get_random_velocity(),
-        });
+        let gamepad = if args.headless.is_some() {
+            None
+        } else {
+            match Gamepad::open(&sdl_context) {
+                Ok(gamepad) => Some(gamepad),
+                Err(err) => {
+                    eprintln!("Warning: {err}, playing without a controller");
+                    None
+                }
+            }
+        };
+
+        // nor does it draw any text, so it doesn't need the font
+        let font = match args.headless {
+            Some(_) => None,
+            None => Some(Self::load_font()?),
+        };
+
+        // a headless game is only simulated, so it keeps whatever it scores or unlocks to itself
+        // rather than touching the player's files
+        let (high_scores, daily_scores, achievements) = match args.headless {
+            Some(_) => (
+                HighScores::default(),
+                HighScores::default(),
+                Achievements::default(),
+            ),
+            None => (
+                HighScores::load("highscore"),
+                HighScores::load("daily"),
+                Achievements::load(),
+            ),
+        };
 
-        Ok(Game {
-            sdl_context,
+        let levels = match &args.level {
+            Some(path) => vec![Level::load(path).map_err(GameError::LevelParse)?],
+            None => campaign::levels(),
+        };
+
+        // a single dropped ball ends the run in these modes
+        let lives = match args.mode {
+            Mode::Survival | Mode::Juggling => 1,
+            Mode::Endless | Mode::Coop => Self::ENDLESS_ESCAPES,
+            _ => args.lives,
+        };
+        let mut top_paddle = Paddle::new(Vector2::new(width / 2.0, 0.0), false, &config);
+        if args.mode == Mode::Computer {
+            top_paddle.velocity *= Self::AI_SPEED_RATIO;
+        }
+
+        let mut game = Game {
+            _sdl_context: sdl_context,
+            event_pump,
             canvas,
+            font,
+            headless_steps: args.headless,
             is_running: true,
-            ticks_count: Instant::now(),
-            balls,
-            paddle_pos: Vector2 {
-                x: Self::WINDOW_WIDTH / 2.0,
-                y: Self::WINDOW_HEIGHT - Self::THICKNESS,
+            is_paused: false,
+            last_input: Instant::now(),
+            // a headless game plays itself so that there is something to simulate
+            demo: args.headless.is_some(),
+            show_fps: false,
+            theme: args.theme,
+            ball_colors: 0,
+            speed_colors: args.speed_colors,
+            debug_draw: args.debug,
+            debug: args.debug,
+            single_step: false,
+            night: args.night,
+            practice: args.practice,
+            mirror: args.mirror || args.mirror_view,
+            mirror_view: args.mirror_view,
+            fps: 0.0,
+            shake: 0.0,
+            shake_offset: (0, 0),
+            effects_rng: StdRng::from_entropy(),
+            config,
+            difficulty: args.difficulty,
+            speed_growth: args.difficulty.params().speed_growth,
+            mode: args.mode,
+            control_mode: args.control_mode,
+            width,
+            height,
+            sounds,
+            gamepad,
+            rng: match args.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
             },
-            paddle_dir: 0,
-        })
+            layout: args.layout,
+            obstacles: Self::place_obstacles(args.layout, args.mode, width, height, &config),
+            portal_pairs: args.layout.portals(width, height, config.thickness),
+            well: args.layout.well(width, height, config.thickness),
+            winds: args.layout.winds(width, height, config.thickness),
+            gravity: None,
+            boss: (args.mode == Mode::Boss).then(|| Boss::new(width, config.thickness)),
+            hazards: Vec::new(),
+            tournament: (args.tournament && args.mode == Mode::Versus)
+                .then(|| Tournament::start(height / 2.0)),
+            auto_spawn: args.auto_spawn,
+            spawn_timer: 0.0,
+            bonus_targets: args.targets
+                && matches!(
+                    args.mode,
+                    Mode::Solo
+                        | Mode::TimeAttack
+                        | Mode::Endless
+                        | Mode::Campaign
+                        | Mode::Coop
+                        | Mode::Boss
+                ),
+            targets: Vec::new(),
+            popups: Vec::new(),
+            bricks: Vec::new(),
+            level: 0,
+            levels,
+            campaign_level: 0,
+            level_time: 0.0,
+            level_hits: 0,
+            base_ball_speed: config.ball_speed,
+            spinner: args.layout.spinner(
+                width,
+                height,
+                config.thickness,
+                config.spinner_speed.to_radians(),
+            ),
+            balls: VecDeque::new(),
+            paddle: Paddle::new(
+                Vector2::new(width / 2.0, height - config.thickness),
+                false,
+                &config,
+            ),
+            partner_paddle: Paddle::new(
+                Vector2::new(width * 3.0 / 4.0, height - config.thickness),
+                false,
+                &config,
+            ),
+            power_ups: Vec::new(),
+            power_up_timer: Self::POWER_UP_INTERVAL,
+            paddle_effects: Vec::new(),
+            paddle_base_width: config.paddle_width,
+            slow_motion_remaining: 0.0,
+            bullet_time: false,
+            sticky_remaining: 0.0,
+            shield: false,
+            shield_flash: 0.0,
+            dash_remaining: 0.0,
+            dash_cooldown: 0.0,
+            left_paddle: Paddle::new(Vector2::new(0.0, height / 2.0), true, &config),
+            right_paddle: Paddle::new(
+                Vector2::new(width - config.thickness, height / 2.0),
+                true,
+                &config,
+            ),
+            left_handicap,
+            right_handicap,
+            left_score: left_handicap.head_start,
+            right_score: right_handicap.head_start,
+            sudden_death: false,
+            sudden_death_banner: 0.0,
+            top_paddle,
+            top_score: 0,
+            rebound: args.rebound,
+            ai_error_margin: Self::AI_ERROR_MARGIN,
+            ai_reaction_timer: 0.0,
+            ai_target_x: width / 2.0,
+            score: 0,
+            high_scores,
+            daily: args.daily.clone(),
+            daily_scores,
+            stats: Stats::default(),
+            achievements,
+            unlocked: VecDeque::new(),
+            unlocked_timer: Self::UNLOCKED_TIME,
+            survival_time: 0.0,
+            best_survival_time: 0.0,
+            juggling_points: 0.0,
+            juggling_timer: Self::JUGGLING_INTERVAL,
+            peak_balls: 0,
+            endless_timer: Self::ENDLESS_START_INTERVAL,
+            endless_interval: Self::ENDLESS_START_INTERVAL,
+            time_remaining: Self::TIME_ATTACK_TIME,
+            rally: 0,
+            longest_rally: 0,
+            misses: 0,
+            lives,
+            side_lives: [lives; 4],
+            initial_lives: lives,
+            target_score: args.target_score,
+            // a headless game has no one to pick from the menu
+            state: match args.headless {
+                Some(_) => GameState::Countdown(Self::COUNTDOWN_TIME),
+                None => GameState::Menu(MenuItem::Start),
+            },
+        };
+        if args.gravity_shift {
+            game.gravity = Some(GravityShift::new(&mut game.rng));
+        }
+        game.place_targets();
+        game.size_versus_paddles(1.0);
+        game.left_paddle.velocity *= left_handicap.speed_scale;
+        game.right_paddle.velocity *= right_handicap.speed_scale;
+        game.face_opponent();
+        game.bricks = game.initial_bricks();
+        if game.mode == Mode::Campaign {
+            game.start_level(0);
+        } else {
+            game.spawn_initial_balls();
+        }
+
+        Ok(game)
     }
 
-    pub fn run(&mut self) {
-        while self.is_running {
-            self.process_input();
-            self.update();
-            self.render();
-        }
+    // load the font the text is drawn in
+    fn load_font() -> Result<Font<'static, 'static>, GameError> {
+        // the TTF context has to outlive the font loaded from it, so keep it for the whole run
+        let ttf_context: &'static Sdl2TtfContext = match sdl2::ttf::init() {
+            Ok(ttf_context) => Box::leak(Box::new(ttf_context)),
+            Err(err) => return Err(GameError::TtfInit(err.to_string())),
+        };
+        let font_path = Self::asset_path(Self::FONT_FILE);
+        ttf_context
+            .load_font(&font_path, Self::FONT_SIZE)
+            .map_err(|message| GameError::FontLoad {
+                path: font_path,
+                message,
+            })
     }
 
-    fn process_input(&mut self) {
-        let mut event_pump = self.sdl_context.event_pump().unwrap_or_else(|err| {
-            eprintln!("Failed to get SDL2 event pump: {err}");
-            process::exit(1);
-        });
+    // open the window sized as asked, no smaller than `min_size` on either side, and get a
+    // canvas to draw the game on
+    fn create_canvas(
+        sdl_context: &sdl2::Sdl,
+        args: &Args,
+        min_size: u32,
+    ) -> Result<Canvas<Window>, GameError> {
+        let video_subsystem = match sdl_context.video() {
+            Ok(video_subsystem) => video_subsystem,
+            Err(err) => return Err(GameError::Video(err)),
+        };
 
-        for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => self.is_running = false,
-                Event::KeyDown {
-                    keycode: Some(Keycode::R),
-                    ..
-                } => {
-                    if self.balls.len() == 5 {
-                        self.balls.pop_back();
-                    }
-                    self.balls.push_front({
-                        Ball {
-                            pos: Vector2 {
-                                x: Self::WINDOW_WIDTH / 2.0,
-                                y: Self::WINDOW_HEIGHT / 2.0,
-                            },
-                            vel: Self::get_random_velocity(),
-                        }
-                    });
-                }
-                _ => {}
-            }
+        let window = video_subsystem
+            .window("Pong", args.width, args.height)
+            .position_centered()
+            .resizable()
+            .build();
+        let mut window = match window {
+            Ok(window) => window,
+            Err(err) => return Err(GameError::WindowCreate(err.to_string())),
+        };
+        if let Err(err) = window.set_minimum_size(min_size, min_size) {
+            return Err(GameError::WindowMinimumSize(err.to_string()));
         }
 
-        self.paddle_dir = 0;
-        for key in event_pump.keyboard_state().pressed_scancodes() {
-            match key {
-                Scancode::A => self.paddle_dir = -1,
-                Scancode::D => self.paddle_dir = 1,
-                _ => {}
-            }
+        match window.into_canvas().build() {
+            Ok(canvas) => Ok(canvas),
+            Err(err) => Err(GameError::CanvasCreate(err.to_string())),
         }
     }
 
-    fn update(&mut self) {
-        // wait until 16ms has elapsed since last frame
-        while !(self.ticks_count.elapsed().as_millis() > 16) {}
-
-        let mut delta_time = self.ticks_count.elapsed().as_secs_f32();
-        // cap delta time to 50ms
-        if delta_time >= 0.05 {
-            delta_time = 0.05;
-        }
-
-        // move paddle
-        if self.paddle_dir != 0 {
-            self.paddle_pos.x += self.paddle_dir as f32 * Self::PADDLE_VEL * delta_time;
-
-            // make sure the paddle doesn't go off the screen
-            if self.paddle_pos.x > (Self::WINDOW_WIDTH - Self::PADDLE_WIDTH / 2.0 - Self::THICKNESS)
-            {
-                self.paddle_pos.x = Self::WINDOW_WIDTH - Self::PADDLE_WIDTH / 2.0 - Self::THICKNESS;
-            } else if self.paddle_pos.x < (Self::THICKNESS + Self::PADDLE_WIDTH / 2.0) {
-                self.paddle_pos.x = Self::THICKNESS + Self::PADDLE_WIDTH / 2.0;
-            }
+    pub fn run(&mut self) {
+        if let Some(steps) = self.headless_steps {
+            self.run_headless(steps);
+            return;
         }
+        let mut last_frame_start = Instant::now();
+        // real time that has passed but hasn't been simulated yet
+        let mut unsimulated = 0.0;
+        while self.is_running {
+            let frame_start = Instant::now();
+            let frame_time = (frame_start - last_frame_start).as_secs_f32();
+            self.measure_fps(frame_time);
+            last_frame_start = frame_start;
 
-        // move balls
-        for ball in &mut self.balls {
-            ball.pos.x += ball.vel.x * delta_time;
-            ball.pos.y += ball.vel.y * delta_time;
-
-            // collision detection with right and left walls
-            if (ball.pos.x <= Self::THICKNESS && ball.vel.x < 0.0)
-                || ((ball.pos.x >= Self::WINDOW_WIDTH - Self::THICKNESS) && ball.vel.x > 0.0)
-            {
-                ball.vel.x = -ball.vel.x;
+            self.process_input();
+            // catch up in fixed steps, but give up on time lost to a long stall rather than
+            // trying to make it all up at once
+            unsimulated += frame_time.min(Self::MAX_FRAME_TIME);
+            while unsimulated >= Self::UPDATE_STEP {
+                self.update(Self::UPDATE_STEP);
+                unsimulated -= Self::UPDATE_STEP;
             }
-
-            // collision detection with top wall
-            if (ball.pos.y <= Self::THICKNESS) && (ball.vel.y < 0.0) {
-                ball.vel.y = -ball.vel.y;
+            if let Err(err) = self.render() {
+                eprintln!("Failed to render: {err}");
+                self.is_running = false;
             }
 
-            // collision detection with paddle
-            if (self.paddle_pos.x - ball.pos.x).abs() <= (Self::PADDLE_WIDTH / 2.0)
-                && (ball.pos.y >= Self::WINDOW_HEIGHT - Self::THICKNESS)
-                && (ball.pos.y <= Self::WINDOW_HEIGHT)
-                && (ball.vel.y > 0.0)
-            {
-                ball.vel.y = -ball.vel.y;
+            // sleep for the rest of the frame instead of spinning the CPU
+            if let Some(remaining) = Self::FRAME_TIME.checked_sub(frame_start.elapsed()) {
+                thread::sleep(remaining);
             }
         }
-
-        self.ticks_count = Instant::now();
     }
 
-    fn render(&mut self) {
-        // draw background
-        self.canvas.set_draw_color(Color::RGB(124, 199, 232));
-        self.canvas.clear();
-
-        self.canvas.set_draw_color(Color::RGB(255, 255, 255));
-
-        // draw top wall
-        let mut wall = Rect::new(0, 0, Self::WINDOW_WIDTH as u32, Self::THICKNESS as u32);
-        self.canvas.fill_rect(wall).unwrap();
-
-        // draw left wall
-        wall.w = Self::THICKNESS as i32;
-        wall.h = (Self::WINDOW_HEIGHT - Self::THICKNESS) as i32;
-
-        self.canvas.fill_rect(wall).unwrap();
-
-        // draw right wall
-        wall.x = (Self::WINDOW_WIDTH - Self::THICKNESS) as i32;
-        wall.w = Self::THICKNESS as i32;
-        self.canvas.fill_rect(wall).unwrap();
-
-        // draw paddle
-        let paddle = Rect::new(
-            (self.paddle_pos.x - Self::PADDLE_WIDTH / 2.0) as i32,
-            self.paddle_pos.y as i32,
-            Self::PADDLE_WIDTH as u32,
-            Self::THICKNESS as u32,
+    // simulate a number of fixed steps as fast as they go, without input or drawing, and report
+    // how long they took, for profiling the physics
+    fn run_headless(&mut self, steps: u32) {
+        let start = Instant::now();
+        for _ in 0..steps {
+            self.drive_demo_paddle();
+            self.update(Self::UPDATE_STEP);
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+        println!(
+            "Simulated {steps} steps in {elapsed:.3}s, {:.0} steps/s, ending on a score of {} \
+             with {} misses",
+            steps as f64 / elapsed,
+            self.score(),
+            self.misses()
         );
-        self.canvas.fill_rect(paddle).unwrap();
+    }
 
-        // draw ball
-        for ball in &self.balls {
-            self.canvas
-                .fill_rect(Rect::new(
-                    (ball.pos.x - Self::THICKNESS / 2.0) as i32,
-                    (ball.pos.y - Self::THICKNESS / 2.0) as i32,
-                    Self::THICKNESS as u32,
-                    Self::THICKNESS as u32,
-                ))
-                .unwrap();
+    // keep a running average of the frame rate from the real time between frames, so that the
+    // number shown doesn't jump around every frame
+    fn measure_fps(&mut self, frame_time: f32) {
+        if frame_time <= 0.0 {
+            return;
         }
+        let fps = 1.0 / frame_time;
+        self.fps = if self.fps == 0.0 {
+            fps
+        } else {
+            self.fps + (fps - self.fps) * Self::FPS_SMOOTHING
+        };
+    }
 
-        self.canvas.present();
+    // get the font to draw text in, which every game that gets drawn has
+    fn font(&self) -> Result<&Font<'static, 'static>, String> {
+        self.font
+            .as_ref()
+            .ok_or_else(|| "Failed to draw text: no font loaded".to_string())
     }
 
-    // get appropriate random velocity for the ball
-    fn get_random_velocity() -> Vector2 {
-        let mut rng = rand::thread_rng();
-        let mut sp_x = rng.gen_range(0..400) as f32;
-        let sp_y = rng.gen_range(-400..-200) as f32;
+    // number of balls bounced back by the paddle
+    pub fn score(&self) -> u32 {
+        self.score
+    }
 
-        if sp_x < 200.0 {
-            sp_x = -(sp_x + 200.0);
+    // number of balls that got past the paddle
+    pub fn misses(&self) -> u32 {
+        self.misses
+    }
+
+    fn process_input(&mut self) {
+        // with the mouse the paddle is taken to be steered the way the cursor moved this frame,
+        // e.g. for launching
+        if self.control_mode == ControlMode::Mouse {
+            self.paddle.dir = 0;
         }
+        let events: Vec<Event> = self.event_pump.poll_iter().collect();
+        for event in events {
+            if let Some(gamepad) = &mut self.gamepad {
+                gamepad.handle_event(&event);
+            }
+            let is_input = match event {
+                Event::KeyDown { .. }
+                | Event::MouseButtonDown { .. }
+                | Event::MouseMotion { .. }
+                | Event::ControllerButtonDown { .. } => true,
+                Event::ControllerAxisMotion { value, .. } => Gamepad::is_pushed(value),
+                _ => false,
+            };
+            if is_input {
+                self.last_input = Instant::now();
+                // any input ends the demo and hands over a fresh game, without doing anything
+                // else
+                if self.demo {
+                    self.demo = false;
+                    self.reset();
+                    continue;
+                }
+            }
+            match event {
+                Event::Quit { .. }
+                | Event::KeyDown {
+                    keycode: Some(Keycode::Escape),
+                    ..
+                } => self.is_running = false,
+                Event::KeyDown {
+                    keycode: Some(Keycode::R),
+                    ..
+                } => match self.state {
+                    // sudden death is played out with a single ball
+                    GameState::Playing if self.sudden_death => {}
+                    GameState::Playing => {
+                        // there is no limit to how many balls can be juggled or dropped in
+                        if self.balls.len() >= self.config.max_balls
+                            && !matches!(self.mode, Mode::Juggling | Mode::Endless | Mode::Coop)
+                        {
+                            self.balls.pop_back();
+                        }
+                        let kind = BallKind::random(&mut self.rng);
+                        self.serve_ball(kind);
+                    }
+                    GameState::GameOver | GameState::Won(_) | GameState::TimeUp => self.reset(),
+                    GameState::Bracket => self.start_next_match(),
+                    GameState::Menu(_) | GameState::Countdown(_) => {}
+                },
+                Event::KeyDown {
+                    keycode: Some(Keycode::M),
+                    ..
+                } if self.state == GameState::Playing && !self.sudden_death => self.split_ball(),
+                // take out the ball put into play last, always leaving one
+                Event::KeyDown {
+                    keycode: Some(Keycode::E),
+                    ..
+                } if self.state == GameState::Playing && self.balls.len() > 1 => {
+                    self.balls.pop_front();
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode @ (Keycode::Up | Keycode::Down)),
+                    ..
+                } => {
+                    if let GameState::Menu(item) = self.state {
+                        let step = if keycode == Keycode::Up { -1 } else { 1 };
+                        self.state = GameState::Menu(item.moved(step));
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Return | Keycode::KpEnter),
+                    ..
+                } => match self.state {
+                    GameState::Menu(MenuItem::Start) => {
+                        self.state = GameState::Countdown(Self::COUNTDOWN_TIME);
+                    }
+                    GameState::Menu(MenuItem::Quit) => self.is_running = false,
+                    GameState::Countdown(_) => self.state = GameState::Playing,
+                    _ => {}
+                },
+                Event::KeyDown {
+                    keycode: Some(Keycode::Period),
+                    ..
+                } if self.debug && self.is_paused => self.single_step = true,
+                Event::KeyDown {
+                    keycode: Some(Keycode::Space),
+                    ..
+                } if self.state == GameState::Bracket => self.start_next_match(),
+                // the countdown can be skipped by those who are ready, with Enter as well
+                Event::KeyDown {
+                    keycode: Some(Keycode::Space),
+                    ..
+                } if matches!(self.state, GameState::Countdown(_)) => {
+                    self.state = GameState::Playing;
+                }
+                // while debugging, Space pauses unless there are balls on the paddle to launch
+                Event::KeyDown {
+                    keycode: Some(Keycode::Space),
+                    ..
+                } if self.debug
+                    && self.state == GameState::Playing
+                    && (self.is_paused
+                        || self.balls.iter().all(|ball| ball.attached.is_none())) =>
+                {
+                    self.is_paused = !self.is_paused;
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Space),
+                    ..
+                }
+                | Event::ControllerButtonDown {
+                    button: Button::A, ..
+                } if self.state == GameState::Playing => self.launch_balls(),
+                Event::KeyDown {
+                    keycode: Some(Keycode::P),
+                    ..
+                } => self.is_paused = !self.is_paused,
+                Event::KeyDown {
+                    keycode: Some(Keycode::LShift),
+                    ..
+                } => self.dash(),
+                Event::KeyDown {
+                    keycode: Some(Keycode::F11),
+                    ..
+                } => self.toggle_fullscreen(),
+                Event::KeyDown {
+                    keycode: Some(Keycode::F3),
+                    ..
+                } => self.show_fps = !self.show_fps,
+                Event::KeyDown {
+                    keycode: Some(Keycode::F4),
+                    ..
+                } => self.debug_draw = !self.debug_draw,
+                Event::KeyDown {
+                    keycode: Some(Keycode::N),
+                    ..
+                } => self.night = !self.night,
+                Event::KeyDown {
+                    keycode: Some(Keycode::T),
+                    ..
+                } => self.practice = !self.practice,
+                Event::KeyDown {
+                    keycode: Some(Keycode::V),
+                    ..
+                } => self.speed_colors = !self.speed_colors,
+                Event::KeyDown {
+                    keycode: Some(Keycode::C),
+                    ..
+                } => {
+                    self.control_mode = match self.control_mode {
+                        ControlMode::Keyboard => ControlMode::Mouse,
+                        ControlMode::Mouse => ControlMode::Keyboard,
+                    };
+                    self.paddle.target = None;
+                }
+                Event::MouseMotion { x, xrel, .. } if self.control_mode == ControlMode::Mouse => {
+                    let (x, xrel) = self.map_mouse(x, xrel);
+                    self.paddle.target = Some(x as f32);
+                    self.paddle.dir = xrel.signum();
+                }
+                Event::Window {
+                    win_event: WindowEvent::Resized(width, height),
+                    ..
+                } => self.resize(width as f32, height as f32),
+                _ => {}
+            }
+        }
+
+        if self.control_mode == ControlMode::Keyboard {
+            self.paddle.dir = 0;
+        }
+        self.left_paddle.dir = 0;
+        self.right_paddle.dir = 0;
+        self.top_paddle.dir = 0;
+        self.partner_paddle.dir = 0;
+        self.bullet_time = false;
+        let keys: Vec<Scancode> = self
+            .event_pump
+            .keyboard_state()
+            .pressed_scancodes()
+            .collect();
+        // the controller steers the paddle when no key does, the same way round as the keys in
+        // mirror mode
+        let gamepad_dir = self.gamepad.as_ref().map_or(0, |gamepad| gamepad.dir());
+        // a key or the controller held down all along counts as the player being there, even
+        // though it sends no new events
+        if !keys.is_empty() || gamepad_dir != 0 {
+            self.last_input = Instant::now();
+        }
+        for key in keys {
+            match self.map_key(key) {
+                Scancode::A if self.control_mode == ControlMode::Keyboard => self.paddle.dir = -1,
+                Scancode::D if self.control_mode == ControlMode::Keyboard => self.paddle.dir = 1,
+                Scancode::W => self.left_paddle.dir = -1,
+                Scancode::S => self.left_paddle.dir = 1,
+                Scancode::Up => self.right_paddle.dir = -1,
+                Scancode::Down => self.right_paddle.dir = 1,
+                Scancode::Left if self.mode == Mode::Coop => self.partner_paddle.dir = -1,
+                Scancode::Right if self.mode == Mode::Coop => self.partner_paddle.dir = 1,
+                Scancode::Left => self.top_paddle.dir = -1,
+                Scancode::Right => self.top_paddle.dir = 1,
+                Scancode::RShift => self.bullet_time = true,
+                _ => {}
+            }
+        }
+        if self.control_mode == ControlMode::Keyboard && self.paddle.dir == 0 {
+            self.paddle.dir = if self.mirror {
+                -gamepad_dir
+            } else {
+                gamepad_dir
+            };
+        }
+
+        // only a game with the player's paddle alone in it plays itself, and not while paused
+        if !self.demo
+            && !self.is_paused
+            && self.paddles().len() == 1
+            && self.last_input.elapsed() >= Self::ATTRACT_IDLE_TIME
+        {
+            self.demo = true;
+            self.reset();
+        }
+        if self.demo {
+            self.drive_demo_paddle();
+        }
+    }
+
+    // steer the player's paddle under the ball coming down closest to it, launching the balls
+    // that sit on it, for the demo
+    fn drive_demo_paddle(&mut self) {
+        self.paddle.target = None;
+        let center = self.paddle.pos.x;
+        let nearest = self
+            .balls
+            .iter()
+            .filter(|ball| ball.vel.y > 0.0 || ball.attached.is_some())
+            .max_by(|a, b| a.pos.y.total_cmp(&b.pos.y));
+        self.paddle.dir = match nearest {
+            Some(ball) if (ball.pos.x - center).abs() > self.paddle.width / 4.0 => {
+                (ball.pos.x - center).signum() as i32
+            }
+            _ => 0,
+        };
+        if self.state == GameState::Playing && self.balls.iter().any(|ball| ball.attached.is_some())
+        {
+            self.launch_balls();
+        }
+    }
+
+    // swap the keys for left and right in mirror mode
+    fn map_key(&self, key: Scancode) -> Scancode {
+        if !self.mirror {
+            return key;
+        }
+        match key {
+            Scancode::A => Scancode::D,
+            Scancode::D => Scancode::A,
+            Scancode::Left => Scancode::Right,
+            Scancode::Right => Scancode::Left,
+            _ => key,
+        }
+    }
+
+    // turn the cursor position and how far it moved to the other side in mirror mode
+    fn map_mouse(&self, x: i32, xrel: i32) -> (i32, i32) {
+        if self.mirror {
+            (self.width as i32 - x, -xrel)
+        } else {
+            (x, xrel)
+        }
+    }
+
+    // start dashing the player's paddle the way it is being steered, if it is and the last dash
+    // was long enough ago
+    fn dash(&mut self) {
+        if self.mode == Mode::Versus
+            || self.state != GameState::Playing
+            || self.control_mode != ControlMode::Keyboard
+            || self.paddle.dir == 0
+            || self.dash_cooldown > 0.0
+        {
+            return;
+        }
+        self.dash_remaining = Self::DASH_TIME;
+        self.dash_cooldown = Self::DASH_COOLDOWN;
+    }
+
+    // switch between a window and the whole screen, laying the field out again for the new size
+    fn toggle_fullscreen(&mut self) {
+        let Some(canvas) = &mut self.canvas else {
+            return;
+        };
+        let window = canvas.window_mut();
+        let fullscreen = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            FullscreenType::True | FullscreenType::Desktop => FullscreenType::Off,
+        };
+        if let Err(err) = window.set_fullscreen(fullscreen) {
+            eprintln!("Warning: Failed to toggle fullscreen: {err}");
+            return;
+        }
+
+        match canvas.output_size() {
+            Ok((width, height)) => self.resize(width as f32, height as f32),
+            Err(err) => eprintln!("Warning: Failed to get window size: {err}"),
+        }
+    }
+
+    // stretch the field to a new window size, moving everything in it along, and keeping the
+    // paddles against their edges
+    fn resize(&mut self, width: f32, height: f32) {
+        let scale = Vector2::new(width / self.width, height / self.height);
+        let rescale = |pos: Vector2| Vector2::new(pos.x * scale.x, pos.y * scale.y);
+        let thickness = self.config.thickness;
+        self.width = width;
+        self.height = height;
+
+        for ball in &mut self.balls {
+            ball.pos = rescale(ball.pos);
+            ball.trail.clear();
+        }
+        for hazard in &mut self.hazards {
+            hazard.pos = rescale(hazard.pos);
+        }
+        if let Some(boss) = &mut self.boss {
+            boss.block = Boss::block(width, thickness);
+        }
+        for power_up in &mut self.power_ups {
+            power_up.pos = rescale(power_up.pos);
+        }
+        self.paddle.pos = Vector2::new(self.paddle.pos.x * scale.x, height - thickness);
+        self.partner_paddle.pos =
+            Vector2::new(self.partner_paddle.pos.x * scale.x, height - thickness);
+        if let Some(target) = &mut self.paddle.target {
+            *target *= scale.x;
+        }
+        self.top_paddle.pos.x *= scale.x;
+        self.left_paddle.pos.y *= scale.y;
+        self.right_paddle.pos = Vector2::new(width - thickness, self.right_paddle.pos.y * scale.y);
+        self.ai_target_x *= scale.x;
+        if let Some(tournament) = &mut self.tournament {
+            tournament.aim *= scale.y;
+        }
+
+        // lay the field out again for the new size, keeping the bricks that are left and how far
+        // the moving parts have got
+        for brick in &mut self.bricks {
+            brick.block.pos = rescale(brick.block.pos);
+            brick.block.size = rescale(brick.block.size);
+        }
+        let angle = self.spinner.as_ref().map_or(0.0, |spinner| spinner.angle);
+        let time = self.well.as_ref().map_or(0.0, |well| well.time);
+        self.lay_out();
+        if let Some(spinner) = &mut self.spinner {
+            spinner.angle = angle;
+        }
+        if let Some(well) = &mut self.well {
+            well.time = time;
+        }
+        self.place_targets();
+    }
+
+    // put the obstacles of the layout into the field from scratch
+    fn lay_out(&mut self) {
+        let thickness = self.config.thickness;
+        self.obstacles = Self::place_obstacles(
+            self.layout,
+            self.mode,
+            self.width,
+            self.height,
+            &self.config,
+        );
+        self.portal_pairs = self.layout.portals(self.width, self.height, thickness);
+        self.winds = self.layout.winds(self.width, self.height, thickness);
+        self.spinner = self.layout.spinner(
+            self.width,
+            self.height,
+            thickness,
+            self.config.spinner_speed.to_radians(),
+        );
+        self.well = self.layout.well(self.width, self.height, thickness);
+
+        // add the obstacles and the wind of the campaign level on top of the layout
+        if self.mode == Mode::Campaign {
+            let level = &self.levels[self.campaign_level];
+            self.obstacles.extend(
+                level
+                    .obstacles
+                    .iter()
+                    .map(|area| area.obstacle(self.width, self.height)),
+            );
+            self.winds.extend(
+                level
+                    .winds
+                    .iter()
+                    .map(|wind| wind.zone(self.width, self.height)),
+            );
+        }
+    }
+
+    // move everything on by `delta_time`, which is always the same so that the game plays the
+    // same however fast it is drawn
+    fn update(&mut self, delta_time: f32) {
+        // the achievements unlocked at the end of a game are still announced over it
+        if !self.unlocked.is_empty() {
+            self.unlocked_timer -= delta_time;
+            if self.unlocked_timer <= 0.0 {
+                self.unlocked.pop_front();
+                self.unlocked_timer = Self::UNLOCKED_TIME;
+            }
+        }
+
+        // the screen settles down even once the game is over
+        self.shake = (self.shake - delta_time / Self::SHAKE_TIME).max(0.0);
+
+        // nothing moves while the game is paused, but for a single step when debugging, before
+        // it starts or once it is over
+        if self.is_paused {
+            if !self.single_step {
+                return;
+            }
+            self.single_step = false;
+        }
+        // the paddles can be lined up during the countdown, while the balls wait for it, the
+        // ones sitting on the player's paddle moving along with it
+        if let GameState::Countdown(remaining) = self.state {
+            self.move_paddles(delta_time);
+            for ball in &mut self.balls {
+                physics::carry_attached(ball, &self.paddle);
+            }
+            self.state = if remaining > delta_time {
+                GameState::Countdown(remaining - delta_time)
+            } else {
+                GameState::Playing
+            };
+            return;
+        }
+        if self.state != GameState::Playing {
+            return;
+        }
+
+        // slow motion only affects the world, so the paddles and balls move slower while input
+        // and frame pacing stay at full rate
+        let world_time = delta_time * self.time_scale();
+        self.slow_motion_remaining = (self.slow_motion_remaining - delta_time).max(0.0);
+
+        // the clock runs at full rate even in slow motion
+        if self.mode == Mode::TimeAttack {
+            self.time_remaining = (self.time_remaining - delta_time).max(0.0);
+        }
+
+        // keep the paddle going at dash speed until the dash is over, and then no faster than it
+        // can normally go
+        self.dash_cooldown = (self.dash_cooldown - world_time).max(0.0);
+        if self.dash_remaining > 0.0 {
+            self.dash_remaining = (self.dash_remaining - world_time).max(0.0);
+            self.paddle.speed = if self.dash_remaining > 0.0 {
+                self.paddle.dir as f32 * self.paddle.velocity * Self::DASH_SPEED_SCALE
+            } else {
+                self.paddle
+                    .speed
+                    .clamp(-self.paddle.velocity, self.paddle.velocity)
+            };
+        }
+
+        let paddle_x = self.paddle.pos.x;
+        self.move_paddles(world_time);
+        match self.mode {
+            Mode::Solo
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Coop
+            | Mode::Campaign
+            | Mode::Boss => self.update_solo(world_time),
+            Mode::Versus => self.update_versus(world_time),
+            Mode::Computer | Mode::TwoPlayer => self.update_top_bottom(world_time),
+            Mode::FourPlayer => self.update_four_player(world_time),
+        }
+        if let Some(spinner) = &mut self.spinner {
+            spinner.update(world_time);
+        }
+        if let Some(well) = &mut self.well {
+            well.update(world_time);
+        }
+        if let Some(gravity) = &mut self.gravity {
+            gravity.update(world_time, &mut self.rng);
+        }
+        self.auto_spawn(world_time);
+        for ball in &mut self.balls {
+            if ball.attached.is_none() {
+                // the speed a ball can pick up from its spin, the well, gravity and the wind is
+                // capped along with the rest below, so that it can still be returned
+                ball.curve(world_time);
+                let drift_time = world_time * ball.kind.drift();
+                if let Some(well) = &self.well {
+                    well.pull(ball.pos, &mut ball.vel, drift_time);
+                }
+                if let Some(gravity) = &self.gravity {
+                    gravity.pull(&mut ball.vel, drift_time);
+                }
+                for wind in &self.winds {
+                    wind.blow(ball.pos, &mut ball.vel, drift_time);
+                }
+                for obstacle in &self.obstacles {
+                    if obstacle.collide(&mut ball.pos, &mut ball.vel, self.config.thickness) {
+                        Self::play(&self.sounds, Sound::Wall);
+                    }
+                }
+                if let Some(spinner) = &self.spinner {
+                    if spinner.collide(&mut ball.pos, &mut ball.vel, self.config.thickness) {
+                        Self::play(&self.sounds, Sound::Wall);
+                    }
+                }
+                if let Some(boss) = &mut self.boss {
+                    if boss.collide(&mut ball.pos, &mut ball.vel, self.config.thickness) {
+                        Self::play(&self.sounds, Sound::Wall);
+                    }
+                }
+
+                ball.portal_cooldown = (ball.portal_cooldown - world_time).max(0.0);
+                if ball.portal_cooldown == 0.0 {
+                    for pair in &self.portal_pairs {
+                        if pair.teleport(&mut ball.pos, &mut ball.vel, self.config.thickness) {
+                            ball.portal_cooldown = Self::PORTAL_COOLDOWN;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        if self.mode == Mode::Breakout {
+            self.collide_bricks();
+        }
+        physics::collide_balls(&mut self.balls, self.config.thickness);
+        for ball in &mut self.balls {
+            ball.clamp_speed(Self::BALL_MIN_SPEED_X, physics::BALL_MAX_SPEED);
+            ball.record_trail();
+        }
+
+        // the match only ends after all the balls have been updated for this frame
+        if let Some(winner) = self.winner() {
+            self.state = GameState::Won(winner);
+            // a match of the tournament won short of the final moves on to the next opponent
+            if let Some(tournament) = &mut self.tournament {
+                if winner == Winner::LeftPlayer && !tournament.is_final() {
+                    tournament.advance();
+                    self.state = GameState::Bracket;
+                }
+            }
+        }
+        if self.mode == Mode::Versus {
+            self.update_sudden_death(delta_time);
+        }
+        if self.mode == Mode::TimeAttack && self.time_remaining == 0.0 {
+            self.state = GameState::TimeUp;
+        }
+        // the demo starts over instead of ending, and whatever it scored counts for nothing
+        if self.demo && self.has_ended() {
+            self.reset();
+        }
+        if self.has_ended() {
+            self.record_high_score();
+        }
+
+        self.stats.paddle_travel += (self.paddle.pos.x - paddle_x).abs();
+        if self.balls.len() >= Achievement::CROWD_BALLS {
+            self.stats.crowd_time += world_time;
+        } else {
+            self.stats.crowd_time = 0.0;
+        }
+        self.stats.won = self.state == GameState::Won(Winner::Player);
+        self.check_achievements();
+    }
+
+    // unlock the achievements the player has just earned, saving them right away, and queue
+    // them up to be announced
+    fn check_achievements(&mut self) {
+        if self.demo {
+            return;
+        }
+        let unlocked = self.achievements.check(&self.stats, self.width);
+        if unlocked.is_empty() {
+            return;
+        }
+        if let Err(err) = self.achievements.save() {
+            eprintln!("Warning: {err}");
+        }
+        if self.unlocked.is_empty() {
+            self.unlocked_timer = Self::UNLOCKED_TIME;
+        }
+        self.unlocked.extend(unlocked);
+    }
+
+    // move the paddles in play the way they are steered, or the computer's after the ball, each
+    // between the walls at the ends of its side
+    fn move_paddles(&mut self, delta_time: f32) {
+        let thickness = self.config.thickness;
+        let (left, right) = (thickness, self.width - thickness);
+        let (top, bottom) = (thickness, self.height - thickness);
+        match self.mode {
+            Mode::Solo
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Campaign
+            | Mode::Boss => self.paddle.update(delta_time, left, right),
+            // in the co-op mode each player keeps to their own half
+            Mode::Coop => {
+                self.paddle.update(delta_time, left, self.width / 2.0);
+                self.partner_paddle
+                    .update(delta_time, self.width / 2.0, right);
+            }
+            Mode::Versus => {
+                self.left_paddle.update(delta_time, top, bottom);
+                if self.tournament.is_some() {
+                    self.move_opponent_paddle(delta_time);
+                } else {
+                    self.right_paddle.update(delta_time, top, bottom);
+                }
+            }
+            Mode::Computer => {
+                self.paddle.update(delta_time, left, right);
+                self.move_ai_paddle(delta_time);
+            }
+            Mode::TwoPlayer => {
+                self.paddle.update(delta_time, left, right);
+                self.top_paddle.update(delta_time, left, right);
+            }
+            Mode::FourPlayer => {
+                for paddle in [&mut self.paddle, &mut self.top_paddle] {
+                    paddle.update(delta_time, left, right);
+                }
+                for paddle in [&mut self.left_paddle, &mut self.right_paddle] {
+                    paddle.update(delta_time, top, bottom);
+                }
+            }
+        }
+    }
+
+    fn update_solo(&mut self, delta_time: f32) {
+        match self.mode {
+            Mode::Survival => self.survive(delta_time),
+            Mode::Juggling => self.juggle(delta_time),
+            Mode::Endless | Mode::Coop => self.drop_balls(delta_time),
+            Mode::Campaign => self.advance_campaign(delta_time),
+            Mode::Boss => self.fight_boss(delta_time),
+            _ => {}
+        }
+        self.update_power_ups(delta_time);
+
+        let (left, right) = (self.config.thickness, self.width - self.config.thickness);
+        let bounce = self.bounce();
+        let mut paddles = vec![Guard {
+            paddle: &self.paddle,
+            inward: Side::Bottom.inward(),
+        }];
+        if self.mode == Mode::Coop {
+            paddles.push(Guard {
+                paddle: &self.partner_paddle,
+                inward: Side::Bottom.inward(),
+            });
+        }
+        let contacts = physics::step(
+            &mut self.balls,
+            &paddles,
+            delta_time,
+            Bounds {
+                left,
+                right,
+                top: self.config.thickness,
+                bottom: f32::INFINITY,
+            },
+            bounce,
+        );
+        self.play_contacts(&contacts);
+        if matches!(
+            self.mode,
+            Mode::Solo
+                | Mode::TimeAttack
+                | Mode::Endless
+                | Mode::Campaign
+                | Mode::Coop
+                | Mode::Boss
+        ) {
+            self.score += contacts.paddle;
+            // shown a little above the paddle, since popups drift down
+            for &pos in &contacts.perfect {
+                self.score += Self::PERFECT_HIT_POINTS;
+                self.show_popup(
+                    format!("Perfect! +{}", Self::PERFECT_HIT_POINTS),
+                    Vector2::new(pos.x, pos.y - self.config.thickness * 4.0),
+                );
+            }
+        }
+        self.rally += contacts.paddle;
+        self.stats.paddle_hits += contacts.paddle;
+        self.level_hits += contacts.paddle;
+        self.hit_targets(&contacts.top);
+        self.update_targets(delta_time);
+
+        self.collide_shield();
+
+        // remove balls that have completely left through the bottom edge, so that each of them
+        // is counted as a miss exactly once and no longer takes up a place among the balls, or
+        // with the gravity mutator as soon as they are past the paddle, since a slow ball could
+        // hover below it or be pulled back up through it
+        let miss_line = if self.gravity.is_some() {
+            self.height
+        } else {
+            self.height + self.config.thickness
+        };
+        let ball_count = self.balls.len();
+        self.balls.retain(|ball| ball.pos.y <= miss_line);
+        let lost = (ball_count - self.balls.len()) as u32;
+        if lost > 0 {
+            self.end_rally();
+            self.misses += lost;
+            self.shake = 1.0;
+            // a lost ball costs nothing in the demo, and time instead of a life when racing the
+            // clock
+            if !self.demo {
+                if self.mode == Mode::TimeAttack {
+                    self.time_remaining =
+                        (self.time_remaining - Self::TIME_ATTACK_PENALTY * lost as f32).max(0.0);
+                } else {
+                    self.lives = self.lives.saturating_sub(lost);
+                }
+            }
+            if self.lives == 0 {
+                self.state = GameState::GameOver;
+                self.best_survival_time = self.best_survival_time.max(self.survival_time);
+            } else if self.balls.is_empty() {
+                self.serve_ball(BallKind::Normal);
+            }
+        }
+    }
+
+    // get how balls come off the paddles as things are now
+    fn bounce(&self) -> Bounce {
+        Bounce {
+            serve_speed: self.config.ball_speed,
+            speed_growth: self.speed_growth,
+            max_speed_scale: self.config.max_speed_scale,
+            spin: self.config.spin,
+            sticky: self.sticky_remaining > 0.0,
+        }
+    }
+
+    fn play_contacts(&self, contacts: &Contacts) {
+        for _ in 0..contacts.walls {
+            Self::play(&self.sounds, Sound::Wall);
+        }
+        for _ in 0..contacts.paddle {
+            Self::play(&self.sounds, Sound::Paddle);
+        }
+        // the wall sound on top of the paddle one makes a perfect hit ring out
+        if !contacts.perfect.is_empty() {
+            Self::play(&self.sounds, Sound::Wall);
+        }
+    }
+
+    // bounce balls off the shield, which lies beyond the paddle and breaks on the first ball it
+    // saves
+    fn collide_shield(&mut self) {
+        for ball in &mut self.balls {
+            if self.shield && (ball.pos.y > self.height) && (ball.vel.y > 0.0) {
+                ball.pos.y = self.height;
+                ball.vel.y = -ball.vel.y;
+                self.shield = false;
+                Self::play(&self.sounds, Sound::Wall);
+                self.shield_flash = Self::SHIELD_FLASH_TIME;
+            }
+        }
+    }
+
+    fn update_versus(&mut self, delta_time: f32) {
+        let bounce = self.bounce();
+        let paddles = [
+            Guard {
+                paddle: &self.left_paddle,
+                inward: Side::Left.inward(),
+            },
+            Guard {
+                paddle: &self.right_paddle,
+                inward: Side::Right.inward(),
+            },
+        ];
+        let contacts = physics::step(
+            &mut self.balls,
+            &paddles,
+            delta_time,
+            Bounds {
+                left: f32::NEG_INFINITY,
+                right: f32::INFINITY,
+                top: self.config.thickness,
+                bottom: self.height - self.config.thickness,
+            },
+            bounce,
+        );
+        self.play_contacts(&contacts);
+        self.rally += contacts.paddle;
+
+        // a ball that gets into a goal scores a point for the opposite player
+        let ball_count = self.balls.len();
+        for ball in &self.balls {
+            if ball.pos.x < -self.config.thickness {
+                self.right_score += 1;
+            } else if ball.pos.x > self.width + self.config.thickness {
+                self.left_score += 1;
+            }
+        }
+        self.balls.retain(|ball| {
+            ball.pos.x >= -self.config.thickness && ball.pos.x <= self.width + self.config.thickness
+        });
+        if self.balls.len() < ball_count {
+            self.end_rally();
+        }
+        if self.balls.is_empty() {
+            self.serve_ball(BallKind::Normal);
+        }
+    }
+
+    // let the opponent of the tournament follow the ball heading its way that is closest to its
+    // paddle, as quickly and as accurately as the opponent can
+    fn move_opponent_paddle(&mut self, delta_time: f32) {
+        let Some(tournament) = &mut self.tournament else {
+            return;
+        };
+        let opponent = tournament.opponent();
+        tournament.aim_timer -= delta_time;
+        if tournament.aim_timer <= 0.0 {
+            tournament.aim_timer = opponent.reaction_time;
+            let nearest = self
+                .balls
+                .iter()
+                .filter(|ball| ball.vel.x > 0.0)
+                .max_by(|a, b| a.pos.x.total_cmp(&b.pos.x))
+                .or_else(|| self.balls.iter().max_by(|a, b| a.pos.x.total_cmp(&b.pos.x)));
+            if let Some(ball) = nearest {
+                let error = self
+                    .rng
+                    .gen_range(-opponent.error_margin..=opponent.error_margin);
+                tournament.aim = ball.pos.y + error;
+            }
+        }
+
+        self.right_paddle.move_towards(
+            tournament.aim,
+            delta_time,
+            self.config.thickness,
+            self.height - self.config.thickness,
+        );
+    }
+
+    // give the right paddle the speed of the opponent of the tournament being played
+    fn face_opponent(&mut self) {
+        if let Some(tournament) = &self.tournament {
+            self.right_paddle.velocity =
+                self.config.paddle_velocity * tournament.opponent().speed_scale;
+        }
+    }
+
+    // play the next match of the tournament from a score of nothing each
+    fn start_next_match(&mut self) {
+        self.face_opponent();
+        self.left_score = 0;
+        self.right_score = 0;
+        self.end_rally();
+        self.balls.clear();
+        self.ball_colors = 0;
+        self.spawn_initial_balls();
+        self.state = GameState::Countdown(Self::COUNTDOWN_TIME);
+    }
+
+    // go into sudden death once both players are a point away from winning, and back out of it
+    // once the match is decided
+    fn update_sudden_death(&mut self, delta_time: f32) {
+        self.sudden_death_banner = (self.sudden_death_banner - delta_time).max(0.0);
+        let match_point = self.target_score - 1;
+        if self.has_ended() {
+            self.exit_sudden_death();
+        } else if !self.sudden_death
+            && match_point > 0
+            && self.left_score == match_point
+            && self.right_score == match_point
+        {
+            self.enter_sudden_death();
+        }
+    }
+
+    fn enter_sudden_death(&mut self) {
+        self.sudden_death = true;
+        self.sudden_death_banner = Self::SUDDEN_DEATH_BANNER_TIME;
+        self.size_versus_paddles(Self::SUDDEN_DEATH_WIDTH_SCALE);
+        self.speed_growth = self.difficulty.params().speed_growth * Self::SUDDEN_DEATH_EXTRA_GROWTH;
+        self.balls.clear();
+        self.serve_ball(BallKind::Normal);
+    }
+
+    // put the paddles and the speed growth back to how they are in a normal match
+    fn exit_sudden_death(&mut self) {
+        self.sudden_death = false;
+        self.sudden_death_banner = 0.0;
+        self.size_versus_paddles(1.0);
+        self.speed_growth = self.difficulty.params().speed_growth;
+    }
+
+    // make the side paddles `scale` times the configured width, on top of the handicaps
+    fn size_versus_paddles(&mut self, scale: f32) {
+        let width = self.config.paddle_width * scale;
+        self.left_paddle.width = width * self.left_handicap.width_scale;
+        self.right_paddle.width = width * self.right_handicap.width_scale;
+    }
+
+    fn update_four_player(&mut self, delta_time: f32) {
+        let thickness = self.config.thickness;
+        let bounce = self.bounce();
+
+        // the side of a player who is out is closed off by a wall
+        let out = |side: Side| self.side_lives[side as usize] == 0;
+        let bounds = Bounds {
+            left: if out(Side::Left) {
+                thickness
+            } else {
+                f32::NEG_INFINITY
+            },
+            right: if out(Side::Right) {
+                self.width - thickness
+            } else {
+                f32::INFINITY
+            },
+            top: if out(Side::Top) {
+                thickness
+            } else {
+                f32::NEG_INFINITY
+            },
+            bottom: if out(Side::Bottom) {
+                self.height - thickness
+            } else {
+                f32::INFINITY
+            },
+        };
+        // the paddles of the players who are out are gone
+        let paddles: Vec<Guard> = [
+            (Side::Bottom, &self.paddle),
+            (Side::Top, &self.top_paddle),
+            (Side::Left, &self.left_paddle),
+            (Side::Right, &self.right_paddle),
+        ]
+        .into_iter()
+        .filter(|&(side, _)| !out(side))
+        .map(|(side, paddle)| Guard {
+            paddle,
+            inward: side.inward(),
+        })
+        .collect();
+        let contacts = physics::step(&mut self.balls, &paddles, delta_time, bounds, bounce);
+        self.play_contacts(&contacts);
+        self.rally += contacts.paddle;
+
+        // a ball that gets past a side costs its player a life, as long as someone else is
+        // still in, so that there is always a last player left
+        let ball_count = self.balls.len();
+        for ball in &self.balls {
+            let side = if ball.pos.x < -thickness {
+                Side::Left
+            } else if ball.pos.x > self.width + thickness {
+                Side::Right
+            } else if ball.pos.y < -thickness {
+                Side::Top
+            } else if ball.pos.y > self.height + thickness {
+                Side::Bottom
+            } else {
+                continue;
+            };
+            let players_in = self.side_lives.iter().filter(|&&lives| lives > 0).count();
+            if players_in > 1 {
+                self.side_lives[side as usize] = self.side_lives[side as usize].saturating_sub(1);
+            }
+        }
+        self.balls.retain(|ball| {
+            ball.pos.x >= -thickness
+                && ball.pos.x <= self.width + thickness
+                && ball.pos.y >= -thickness
+                && ball.pos.y <= self.height + thickness
+        });
+        if self.balls.len() < ball_count {
+            self.end_rally();
+        }
+        if self.balls.is_empty() {
+            self.serve_ball(BallKind::Normal);
+        }
+    }
+
+    fn update_top_bottom(&mut self, delta_time: f32) {
+        // power-ups only ever help the bottom paddle, so they would be unfair between players
+        if self.mode == Mode::Computer {
+            self.update_power_ups(delta_time);
+        }
+
+        let bounce = self.bounce();
+        let paddles = [
+            Guard {
+                paddle: &self.paddle,
+                inward: Side::Bottom.inward(),
+            },
+            Guard {
+                paddle: &self.top_paddle,
+                inward: Side::Top.inward(),
+            },
+        ];
+        let contacts = physics::step(
+            &mut self.balls,
+            &paddles,
+            delta_time,
+            Bounds {
+                left: self.config.thickness,
+                right: self.width - self.config.thickness,
+                // the top edge, if balls are to bounce back from behind the computer
+                top: if self.rebound { 0.0 } else { f32::NEG_INFINITY },
+                bottom: f32::INFINITY,
+            },
+            bounce,
+        );
+        self.play_contacts(&contacts);
+        self.rally += contacts.paddle;
+        self.stats.paddle_hits += contacts.first_paddle;
+        self.collide_shield();
+
+        // a ball that escapes past a paddle scores a point for the opposite side
+        let ball_count = self.balls.len();
+        for ball in &self.balls {
+            if ball.pos.y < -self.config.thickness {
+                self.score += 1;
+            } else if ball.pos.y > self.height + self.config.thickness {
+                self.top_score += 1;
+                self.shake = 1.0;
+            }
+        }
+        self.balls.retain(|ball| {
+            ball.pos.y >= -self.config.thickness
+                && ball.pos.y <= self.height + self.config.thickness
+        });
+        if self.balls.len() < ball_count {
+            self.end_rally();
+        }
+        if self.balls.is_empty() {
+            self.serve_ball(BallKind::Normal);
+        }
+    }
+
+    // get the best score of the mode so far, counting the current one
+    fn best_score(&self) -> u32 {
+        let best = match &self.daily {
+            Some(daily) => self.daily_scores.get(&daily.date),
+            None => self
+                .mode
+                .high_score_name()
+                .map_or(0, |name| self.high_scores.get(name)),
+        };
+        best.max(self.score)
+    }
+
+    // keep the score of the daily challenge under its date, apart from the ones of the mode
+    fn record_high_score(&mut self) {
+        if let Some(daily) = &self.daily {
+            if let Err(err) = self.daily_scores.submit(&daily.date, self.score) {
+                eprintln!("Warning: {err}");
+            }
+        } else if let Some(name) = self.mode.high_score_name() {
+            if let Err(err) = self.high_scores.submit(name, self.score) {
+                eprintln!("Warning: {err}");
+            }
+        }
+    }
+
+    // count up the time the player has lasted, which is their score, and shrink the paddle by
+    // the same share every second down to its minimum width
+    fn survive(&mut self, delta_time: f32) {
+        self.survival_time += delta_time;
+        self.score = self.survival_time as u32;
+        self.paddle_base_width = (self.paddle_base_width
+            * (1.0 - Self::SURVIVAL_SHRINK_RATE).powf(delta_time))
+        .max(self.config.paddle_width * Self::SURVIVAL_MIN_WIDTH_SCALE);
+    }
+
+    // score a point a second for every ball in the air, and throw in another ball now and then
+    fn juggle(&mut self, delta_time: f32) {
+        self.juggling_points += self.balls.len() as f32 * delta_time;
+        self.score = self.juggling_points as u32;
+
+        self.juggling_timer -= delta_time;
+        if self.juggling_timer <= 0.0 {
+            self.juggling_timer = Self::JUGGLING_INTERVAL;
+            self.drop_ball();
+        }
+        self.peak_balls = self.peak_balls.max(self.balls.len());
+    }
+
+    // count up the time spent on the campaign level, and go on to the next one once its goal is
+    // met, winning after the last one
+    fn advance_campaign(&mut self, delta_time: f32) {
+        self.level_time += delta_time;
+        if !self
+            .current_level()
+            .goal
+            .is_met(self.level_time, self.level_hits)
+        {
+            return;
+        }
+        if self.campaign_level + 1 < self.levels.len() {
+            self.start_level(self.campaign_level + 1);
+        } else {
+            self.state = GameState::Won(Winner::Player);
+        }
+    }
+
+    // let the boss attack, move its hazards down and shrink the paddle for a while with the ones
+    // that touch it, and win once the boss has no hit points left
+    fn fight_boss(&mut self, delta_time: f32) {
+        let Some(boss) = &mut self.boss else {
+            return;
+        };
+        if let Some(hazard) = boss.update(delta_time, self.config.thickness, &mut self.rng) {
+            self.hazards.push(hazard);
+        }
+        if boss.is_defeated() {
+            self.state = GameState::Won(Winner::Player);
+        }
+
+        let paddle = self.paddle.rect();
+        let mut touched = 0;
+        self.hazards.retain_mut(|hazard| {
+            hazard.fall(delta_time);
+            if hazard.touches(paddle) {
+                touched += 1;
+                return false;
+            }
+            hazard.pos.y <= self.height
+        });
+        for _ in 0..touched {
+            self.paddle_effects.push(PaddleEffect {
+                scale: Self::HAZARD_EFFECT_SCALE,
+                remaining: Self::HAZARD_EFFECT_TIME,
+            });
+        }
+    }
+
+    fn current_level(&self) -> &Level {
+        &self.levels[self.campaign_level]
+    }
+
+    // set the field up for a level of the campaign with the paddle back in the center and new
+    // balls, keeping the score and the lives
+    fn start_level(&mut self, index: usize) {
+        self.campaign_level = index;
+        self.level_time = 0.0;
+        self.level_hits = 0;
+        let level = self.current_level();
+        let (layout, ball_speed_scale) = (level.layout, level.ball_speed_scale);
+        let paddle_width = self.config.paddle_width * level.paddle_width_scale;
+        self.layout = layout;
+        self.config.ball_speed = self.base_ball_speed * ball_speed_scale;
+        self.lay_out();
+
+        self.paddle.pos.x = self.width / 2.0;
+        self.paddle.last_along = self.paddle.pos.x;
+        self.paddle.speed = 0.0;
+        self.paddle_effects.clear();
+        self.paddle_base_width = paddle_width;
+        self.paddle.width = paddle_width;
+        self.power_ups.clear();
+        self.balls.clear();
+        self.spawn_initial_balls();
+    }
+
+    // drop a ball in now and then, sooner every time down to the shortest interval
+    fn drop_balls(&mut self, delta_time: f32) {
+        self.endless_timer -= delta_time;
+        if self.endless_timer <= 0.0 {
+            self.endless_interval = (self.endless_interval * Self::ENDLESS_INTERVAL_SCALE)
+                .max(Self::ENDLESS_MIN_INTERVAL);
+            self.endless_timer = self.endless_interval;
+            self.drop_ball();
+        }
+    }
+
+    // throw a ball into play from the center of the field, as it is served
+    fn drop_ball(&mut self) {
+        let vel = self.get_random_velocity(BallKind::Normal);
+        let color = self.next_ball_color(BallKind::Normal);
+        self.balls.push_front(Ball {
+            pos: self.drop_position(),
+            vel,
+            kind: BallKind::Normal,
+            color,
+            attached: None,
+            stuck: false,
+            speed_scale: 1.0,
+            portal_cooldown: 0.0,
+            spin: 0.0,
+            trail: VecDeque::new(),
+        });
+    }
+
+    // throw another ball into play once enough time has passed since the last one, as long as
+    // there is room for it
+    fn auto_spawn(&mut self, delta_time: f32) {
+        let Some(interval) = self.auto_spawn else {
+            return;
+        };
+        self.spawn_timer += delta_time;
+        if self.spawn_timer < interval {
+            return;
+        }
+        self.spawn_timer = 0.0;
+        // sudden death is played out with a single ball
+        if self.balls.len() >= self.config.max_balls || self.sudden_death {
+            return;
+        }
+        match self.mode {
+            // a ball served in these would wait on the paddle to be launched
+            Mode::Solo
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Coop
+            | Mode::Campaign
+            | Mode::Boss
+            | Mode::Computer => self.drop_ball(),
+            Mode::Versus | Mode::TwoPlayer | Mode::FourPlayer => self.serve_ball(BallKind::Normal),
+        }
+    }
+
+    fn drop_position(&self) -> Vector2 {
+        Vector2::new(self.width / 2.0, self.height / 3.0)
+    }
+
+    // put the bonus targets on the top wall from scratch, if there are any
+    fn place_targets(&mut self) {
+        self.targets.clear();
+        if !self.bonus_targets {
+            return;
+        }
+        for _ in 0..Self::TARGET_COUNT {
+            if let Some(x) = self.free_target_x(None) {
+                self.targets.push(Target::new(x));
+            }
+        }
+    }
+
+    // pick a random place along the top wall for a target, clear of the side walls and the
+    // targets other than the one at `moving`, or None if the tries at it all ran into another
+    fn free_target_x(&mut self, moving: Option<usize>) -> Option<f32> {
+        let (min, max) = (
+            self.config.thickness,
+            self.width - self.config.thickness - Target::WIDTH,
+        );
+        if max < min {
+            return None;
+        }
+        (0..Self::TARGET_PLACE_TRIES)
+            .map(|_| self.rng.gen_range(min..=max))
+            .find(|&x| {
+                !self
+                    .targets
+                    .iter()
+                    .enumerate()
+                    .any(|(i, target)| Some(i) != moving && target.overlaps(x))
+            })
+    }
+
+    // score the targets hit by balls bouncing off the top wall at `hits`
+    fn hit_targets(&mut self, hits: &[f32]) {
+        for &x in hits {
+            let Some(target) = self.targets.iter_mut().find(|target| target.contains(x)) else {
+                continue;
+            };
+            target.hit();
+            self.score += Self::TARGET_POINTS;
+            self.show_popup(
+                format!("+{}", Self::TARGET_POINTS),
+                Vector2::new(x, self.config.thickness * 2.0),
+            );
+        }
+    }
+
+    // move the targets that were hit to new places once they are due back, or leave them to try
+    // again next time if there is no room for them yet
+    fn update_targets(&mut self, delta_time: f32) {
+        for i in 0..self.targets.len() {
+            if !self.targets[i].update(delta_time) {
+                continue;
+            }
+            if let Some(x) = self.free_target_x(Some(i)) {
+                self.targets[i] = Target::new(x);
+            }
+        }
+        for popup in &mut self.popups {
+            popup.pos.y += Self::POPUP_SPEED * delta_time;
+            popup.remaining -= delta_time;
+        }
+        self.popups.retain(|popup| popup.remaining > 0.0);
+    }
+
+    fn show_popup(&mut self, text: String, pos: Vector2) {
+        self.popups.push(Popup {
+            text,
+            pos,
+            remaining: Self::POPUP_TIME,
+        });
+    }
+
+    // drop power-ups from the top now and then, let the player catch them with the paddle, and
+    // resize the paddle according to the effects that are still active
+    fn update_power_ups(&mut self, delta_time: f32) {
+        let size = self.config.thickness;
+
+        self.power_up_timer -= delta_time;
+        if self.power_up_timer <= 0.0 {
+            self.power_up_timer = Self::POWER_UP_INTERVAL;
+            self.power_ups.push(PowerUp {
+                pos: Vector2 {
+                    x: self.rng.gen_range(size * 2.0..self.width - size * 2.0),
+                    y: size,
+                },
+                kind: PowerUpKind::random(&mut self.rng),
+            });
+        }
+
+        let mut caught = Vec::new();
+        for power_up in &mut self.power_ups {
+            power_up.pos.y += Self::POWER_UP_SPEED * delta_time;
+        }
+        self.power_ups.retain(|power_up| {
+            let touches_paddle = (power_up.pos.x - self.paddle.pos.x).abs()
+                <= self.paddle.width / 2.0 + size
+                && power_up.pos.y + size / 2.0 >= self.paddle.pos.y
+                && power_up.pos.y - size / 2.0 <= self.paddle.pos.y + size;
+            if touches_paddle {
+                caught.push(power_up.kind);
+            }
+            !touches_paddle && power_up.pos.y <= self.height + size
+        });
+        for kind in caught {
+            self.apply_power_up(kind);
+        }
+
+        self.shield_flash = (self.shield_flash - delta_time).max(0.0);
+
+        // let go of everything stuck to the paddle when it stops being sticky
+        if self.sticky_remaining > 0.0 {
+            self.sticky_remaining -= delta_time;
+            if self.sticky_remaining <= 0.0 {
+                self.release_stuck_balls();
+            }
+        }
+
+        // the width is always worked out from scratch, so an effect running out restores the
+        // paddle no matter what else was caught in the meantime
+        for effect in &mut self.paddle_effects {
+            effect.remaining -= delta_time;
+        }
+        self.paddle_effects.retain(|effect| effect.remaining > 0.0);
+        let scale: f32 = self
+            .paddle_effects
+            .iter()
+            .map(|effect| effect.scale)
+            .product();
+        self.paddle.width =
+            (self.paddle_base_width * scale).min(self.width - 4.0 * self.config.thickness);
+    }
+
+    fn apply_power_up(&mut self, kind: PowerUpKind) {
+        match kind {
+            PowerUpKind::Grow => self.paddle_effects.push(PaddleEffect {
+                scale: 1.5,
+                remaining: Self::PADDLE_EFFECT_TIME,
+            }),
+            PowerUpKind::Shrink => self.paddle_effects.push(PaddleEffect {
+                scale: 0.5,
+                remaining: Self::PADDLE_EFFECT_TIME,
+            }),
+            PowerUpKind::Multiball => self.split_ball(),
+            // catching another one while slowed down makes it last longer, not slower
+            PowerUpKind::SlowMotion => self.slow_motion_remaining += Self::SLOW_MOTION_TIME,
+            PowerUpKind::Sticky => self.sticky_remaining = Self::STICKY_TIME,
+            // the shield only ever takes one hit, so catching another one while it is up changes
+            // nothing
+            PowerUpKind::Shield => self.shield = true,
+        }
+    }
+
+    // get how fast the world moves compared to real time, easing back to full speed over the
+    // last moments of slow motion so that the speed-up isn't sudden, and going no faster than
+    // bullet time while its key is held
+    fn time_scale(&self) -> f32 {
+        let slowness = (self.slow_motion_remaining / Self::SLOW_MOTION_WIND_DOWN).min(1.0);
+        let scale = 1.0 - (1.0 - Self::SLOW_MOTION_SCALE) * slowness;
+        if self.bullet_time {
+            scale.min(Self::BULLET_TIME_SCALE)
+        } else {
+            scale
+        }
+    }
+
+    // get the color for a new ball of `kind`, going round the colors of the theme
+    fn next_ball_color(&mut self, kind: BallKind) -> Color {
+        let palette = self.theme.balls;
+        let color = palette[self.ball_colors % palette.len()];
+        self.ball_colors += 1;
+        kind.color(color)
+    }
+
+    // split a moving ball into three, with the two new ones heading off at an angle to either
+    // side of it, as long as there is room for more balls
+    fn split_ball(&mut self) {
+        let Some(ball) = self.balls.iter().find(|ball| ball.attached.is_none()) else {
+            return;
+        };
+        let (pos, vel, kind, speed_scale, spin) =
+            (ball.pos, ball.vel, ball.kind, ball.speed_scale, ball.spin);
+
+        for angle in [-Self::SPLIT_ANGLE, Self::SPLIT_ANGLE] {
+            if self.balls.len() >= self.config.max_balls || vel.length() == 0.0 {
+                return;
+            }
+
+            let vel = vel.rotate(angle);
+            let color = self.next_ball_color(kind);
+            // start a ball's width apart so that the new balls don't overlap the original
+            self.balls.push_front(Ball {
+                pos: pos + vel.normalize() * self.config.thickness,
+                vel,
+                kind,
+                color,
+                attached: None,
+                stuck: false,
+                speed_scale,
+                portal_cooldown: 0.0,
+                spin,
+                trail: VecDeque::new(),
+            });
+        }
+    }
+
+    // let the computer follow the ball closest to its paddle, only re-aiming after a short
+    // reaction delay and with some error so that it can be beaten
+    fn move_ai_paddle(&mut self, delta_time: f32) {
+        self.ai_reaction_timer -= delta_time;
+        if self.ai_reaction_timer <= 0.0 {
+            self.ai_reaction_timer = Self::AI_REACTION_TIME;
+
+            // prefer balls that are heading towards the computer
+            let nearest = self
+                .balls
+                .iter()
+                .filter(|ball| ball.vel.y < 0.0)
+                .min_by(|a, b| a.pos.y.total_cmp(&b.pos.y))
+                .or_else(|| self.balls.iter().min_by(|a, b| a.pos.y.total_cmp(&b.pos.y)));
+            if let Some(ball) = nearest {
+                let error = if self.ai_error_margin > 0.0 {
+                    self.rng
+                        .gen_range(-self.ai_error_margin..=self.ai_error_margin)
+                } else {
+                    0.0
+                };
+                self.ai_target_x = ball.pos.x + error;
+            }
+        }
+
+        self.top_paddle.move_towards(
+            self.ai_target_x,
+            delta_time,
+            self.config.thickness,
+            self.width - self.config.thickness,
+        );
+    }
+
+    fn play(sounds: &Option<Sounds>, sound: Sound) {
+        if let Some(sounds) = sounds {
+            sounds.play(sound);
+        }
+    }
+
+    // start counting the rally again, remembering it if it was the longest yet
+    fn end_rally(&mut self) {
+        self.longest_rally = self.longest_rally.max(self.rally);
+        self.rally = 0;
+    }
+
+    // break bricks that balls run into, bouncing the balls off them, and lay out a new set of
+    // bricks once they are all gone
+    fn collide_bricks(&mut self) {
+        for ball in &mut self.balls {
+            if ball.attached.is_some() {
+                continue;
+            }
+            // a ball hits only one brick at a time, so that it doesn't bounce back and forth
+            // between two of them when it clips both
+            let hit = self.bricks.iter_mut().find(|brick| {
+                brick
+                    .block
+                    .collide(&mut ball.pos, &mut ball.vel, self.config.thickness)
+            });
+            if let Some(brick) = hit {
+                brick.hp -= 1;
+                if brick.hp == 0 {
+                    self.score += 1;
+                }
+                Self::play(&self.sounds, Sound::Wall);
+            }
+        }
+        self.bricks.retain(|brick| brick.hp > 0);
+
+        if self.bricks.is_empty() {
+            self.level += 1;
+            self.bricks = Brick::layout(self.level, self.width, self.height, self.config.thickness);
+        }
+    }
+
+    // whether the game is over, rather than being played or about to go on after a countdown
+    fn has_ended(&self) -> bool {
+        matches!(
+            self.state,
+            GameState::GameOver | GameState::Won(_) | GameState::TimeUp | GameState::Bracket
+        )
+    }
+
+    // get who has reached the target score, if anyone
+    fn winner(&self) -> Option<Winner> {
+        let (winner, score, other_winner, other_score) = match self.mode {
+            Mode::Solo => (Winner::Player, self.score, Winner::Player, 0),
+            // breaking bricks goes on until the player runs out of lives
+            Mode::Breakout => return None,
+            // and so do lasting in the modes played until the balls are lost, and racing the clock
+            Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Coop
+            | Mode::Campaign
+            | Mode::Boss => return None,
+            Mode::Versus => (
+                Winner::LeftPlayer,
+                self.left_score,
+                Winner::RightPlayer,
+                self.right_score,
+            ),
+            Mode::Computer => (Winner::Player, self.score, Winner::Computer, self.top_score),
+            // the last player with lives left wins
+            Mode::FourPlayer => {
+                let mut players_in = Side::ALL
+                    .into_iter()
+                    .filter(|&side| self.side_lives[side as usize] > 0);
+                return match (players_in.next(), players_in.next()) {
+                    (Some(side), None) => Some(side.winner()),
+                    _ => None,
+                };
+            }
+            Mode::TwoPlayer => (
+                Winner::BottomPlayer,
+                self.score,
+                Winner::TopPlayer,
+                self.top_score,
+            ),
+        };
+
+        if score >= self.target_score {
+            Some(winner)
+        } else if other_score >= self.target_score {
+            Some(other_winner)
+        } else {
+            None
+        }
+    }
+
+    // draw the game, unless it has no window to be drawn in
+    fn render(&mut self) -> Result<(), String> {
+        let Some(mut canvas) = self.canvas.take() else {
+            return Ok(());
+        };
+        self.shake_offset = if self.shake > 0.0 {
+            let max = Self::SHAKE_MAX_OFFSET * self.shake.min(1.0);
+            (
+                self.effects_rng.gen_range(-max..=max) as i32,
+                self.effects_rng.gen_range(-max..=max) as i32,
+            )
+        } else {
+            (0, 0)
+        };
+        let drawn = self.draw(&mut canvas);
+        self.canvas = Some(canvas);
+        drawn
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+        // draw background
+        canvas.set_draw_color(self.theme.background);
+        canvas.clear();
+
+        canvas.set_draw_color(self.theme.foreground);
+
+        self.draw_net(canvas)?;
+
+        // draw top wall
+        let mut wall = Rect::new(0, 0, self.width as u32, self.config.thickness as u32);
+        if !matches!(
+            self.mode,
+            Mode::Computer | Mode::TwoPlayer | Mode::FourPlayer
+        ) {
+            canvas.fill_rect(self.field_rect(wall))?;
+        }
+
+        match self.mode {
+            Mode::Solo
+            | Mode::Computer
+            | Mode::TwoPlayer
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Coop
+            | Mode::Campaign
+            | Mode::Boss => {
+                // draw left wall
+                wall.w = self.config.thickness as i32;
+                wall.h = (self.height - self.config.thickness) as i32;
+
+                canvas.fill_rect(self.field_rect(wall))?;
+
+                // draw right wall
+                wall.x = (self.width - self.config.thickness) as i32;
+                wall.w = self.config.thickness as i32;
+                canvas.fill_rect(self.field_rect(wall))?;
+
+                // draw shield, fading out for a moment after it breaks
+                let shield = Rect::new(
+                    self.config.thickness as i32,
+                    (self.height - self.config.thickness / 4.0) as i32,
+                    (self.width - self.config.thickness * 2.0) as u32,
+                    (self.config.thickness / 4.0) as u32,
+                );
+                if self.shield {
+                    canvas.set_draw_color(PowerUpKind::Shield.color());
+                    canvas.fill_rect(self.field_rect(shield))?;
+                } else if self.shield_flash > 0.0 {
+                    let (r, g, b) = PowerUpKind::Shield.color().rgb();
+                    let alpha = 255.0 * self.shield_flash / Self::SHIELD_FLASH_TIME;
+                    canvas.set_blend_mode(BlendMode::Blend);
+                    canvas.set_draw_color(Color::RGBA(r, g, b, alpha as u8));
+                    canvas.fill_rect(self.field_rect(Rect::new(
+                        shield.x(),
+                        (self.height - self.config.thickness) as i32,
+                        shield.width(),
+                        self.config.thickness as u32,
+                    )))?;
+                    canvas.set_blend_mode(BlendMode::None);
+                }
+                canvas.set_draw_color(self.theme.foreground);
+
+                // draw paddle, tinted while balls stick to it
+                if self.sticky_remaining > 0.0 {
+                    canvas.set_draw_color(PowerUpKind::Sticky.color());
+                }
+                self.draw_capsule(canvas, self.paddle.rect())?;
+                canvas.set_draw_color(self.theme.foreground);
+                if self.mode == Mode::Coop {
+                    self.draw_capsule(canvas, self.partner_paddle.rect())?;
+                }
+
+                // draw how far the dash has recharged as a bar filling up above the paddle
+                if self.dash_cooldown > 0.0 {
+                    let paddle = self.paddle.rect();
+                    let charged = 1.0 - self.dash_cooldown / Self::DASH_COOLDOWN;
+                    canvas.set_draw_color(Color::RGB(90, 90, 110));
+                    canvas.fill_rect(self.field_rect(Rect::new(
+                        paddle.x(),
+                        paddle.y() - 6,
+                        (paddle.width() as f32 * charged) as u32,
+                        3,
+                    )))?;
+                    canvas.set_draw_color(self.theme.foreground);
+                }
+
+                // draw top paddle
+                if matches!(self.mode, Mode::Computer | Mode::TwoPlayer) {
+                    self.draw_capsule(canvas, self.top_paddle.rect())?;
+                }
+            }
+            Mode::Versus => {
+                // draw bottom wall
+                wall.y = (self.height - self.config.thickness) as i32;
+                canvas.fill_rect(self.field_rect(wall))?;
+
+                // draw paddles
+                for rect in [self.left_paddle.rect(), self.right_paddle.rect()] {
+                    self.draw_capsule(canvas, rect)?;
+                }
+            }
+            Mode::FourPlayer => {
+                // draw the walls of the players who are out, and the paddles of the rest in
+                // their own colors
+                for side in Side::ALL {
+                    if self.side_lives[side as usize] == 0 {
+                        canvas.set_draw_color(self.theme.foreground);
+                        canvas.fill_rect(self.field_rect(self.side_wall(side)))?;
+                    } else {
+                        canvas.set_draw_color(side.color());
+                        self.draw_capsule(canvas, self.side_paddle(side).rect())?;
+                    }
+                }
+                canvas.set_draw_color(self.theme.foreground);
+            }
+        }
+
+        // draw the boss, flashing white as it is hit, with its health bar above it, and the
+        // hazards it dropped
+        if let Some(boss) = &self.boss {
+            let block = boss.block.pos;
+            let size = boss.block.size;
+            if !boss.is_defeated() {
+                canvas.set_draw_color(if boss.hit_flash > 0.0 {
+                    Color::RGB(255, 255, 255)
+                } else {
+                    Self::BOSS_COLOR
+                });
+                canvas.fill_rect(self.field_rect(Rect::new(
+                    block.x as i32,
+                    block.y as i32,
+                    size.x as u32,
+                    size.y as u32,
+                )))?;
+            }
+
+            let bar = Rect::new(
+                block.x as i32,
+                (block.y - self.config.thickness * 1.5) as i32,
+                size.x as u32,
+                (self.config.thickness / 2.0) as u32,
+            );
+            canvas.set_draw_color(self.theme.foreground);
+            canvas.draw_rect(self.field_rect(bar))?;
+            let health = boss.hp as f32 / Boss::MAX_HP as f32;
+            canvas.set_draw_color(Self::BOSS_COLOR);
+            canvas.fill_rect(self.field_rect(Rect::new(
+                bar.x(),
+                bar.y(),
+                (bar.width() as f32 * health) as u32,
+                bar.height(),
+            )))?;
+        }
+        canvas.set_draw_color(Self::HAZARD_COLOR);
+        for hazard in &self.hazards {
+            canvas.fill_rect(self.field_rect(hazard.rect()))?;
+        }
+        canvas.set_draw_color(self.theme.foreground);
+
+        // draw obstacles
+        for obstacle in &self.obstacles {
+            canvas.fill_rect(self.field_rect(Rect::new(
+                obstacle.pos.x as i32,
+                obstacle.pos.y as i32,
+                obstacle.size.x as u32,
+                obstacle.size.y as u32,
+            )))?;
+        }
+
+        // draw portals in the colors of their pairs, with a line towards where balls come out
+        for pair in &self.portal_pairs {
+            for (portal, color) in pair.portals.iter().zip(pair.colors) {
+                let half = portal.size / 2.0;
+                canvas.set_draw_color(color);
+                canvas.fill_rect(self.field_rect(Rect::new(
+                    (portal.center.x - half) as i32,
+                    (portal.center.y - half) as i32,
+                    portal.size as u32,
+                    portal.size as u32,
+                )))?;
+
+                let exit = portal.center + portal.facing * half;
+                canvas.set_draw_color(self.theme.foreground);
+                canvas.draw_line(
+                    self.field_point((portal.center.x as i32, portal.center.y as i32)),
+                    self.field_point((exit.x as i32, exit.y as i32)),
+                )?;
+            }
+        }
+
+        // draw bricks
+        for brick in &self.bricks {
+            canvas.set_draw_color(brick.color());
+            canvas.fill_rect(self.field_rect(Rect::new(
+                brick.block.pos.x as i32,
+                brick.block.pos.y as i32,
+                brick.block.size.x as u32,
+                brick.block.size.y as u32,
+            )))?;
+        }
+        canvas.set_draw_color(self.theme.foreground);
+
+        // draw wind zones as a faint tint with arrows pointing the way the wind blows
+        canvas.set_blend_mode(BlendMode::Blend);
+        for wind in &self.winds {
+            canvas.set_draw_color(Color::RGBA(120, 200, 230, 40));
+            canvas.fill_rect(self.field_rect(Rect::new(
+                wind.pos.x as i32,
+                wind.pos.y as i32,
+                wind.size.x as u32,
+                wind.size.y as u32,
+            )))?;
+
+            canvas.set_draw_color(Color::RGBA(120, 200, 230, 120));
+            let direction = wind.force.normalize();
+            let arrow = direction * (wind.size.y / 2.0);
+            let center_y = wind.pos.y + wind.size.y / 2.0;
+            let count = (wind.size.x / (wind.size.y * 2.0)) as i32;
+            for i in 0..count {
+                let tip = Vector2::new(
+                    wind.pos.x + wind.size.x * (i as f32 + 0.5) / count as f32,
+                    center_y,
+                ) + arrow * 0.5;
+                for side in [-1.0, 1.0] {
+                    let tail = tip - arrow + arrow.rotate(std::f32::consts::FRAC_PI_2) * side;
+                    canvas.draw_line(
+                        self.field_point((tip.x as i32, tip.y as i32)),
+                        self.field_point((tail.x as i32, tail.y as i32)),
+                    )?;
+                }
+            }
+        }
+        canvas.set_blend_mode(BlendMode::None);
+        canvas.set_draw_color(self.theme.foreground);
+
+        // draw gravity well as a faint pulsing halo around a solid core
+        if let Some(well) = &self.well {
+            let (x, y) = (well.center.x as i32, well.center.y as i32);
+            let (radius, halo) = (well.radius as i32, (well.pulse_radius() * 2.0) as i32);
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(Color::RGBA(150, 90, 220, 70));
+            self.draw_circle(canvas, x, y, halo)?;
+            canvas.set_blend_mode(BlendMode::None);
+            canvas.set_draw_color(Color::RGB(150, 90, 220));
+            self.draw_circle(canvas, x, y, radius)?;
+            canvas.set_draw_color(self.theme.foreground);
+        }
+
+        // draw spinner as lines side by side across its thickness
+        if let Some(spinner) = &self.spinner {
+            let (start, end) = spinner.ends();
+            let across = (end - start)
+                .normalize()
+                .rotate(std::f32::consts::FRAC_PI_2);
+            let steps = (spinner.thickness * 2.0) as i32;
+            for step in 0..=steps {
+                let offset = across * (spinner.thickness * (step as f32 / steps as f32 - 0.5));
+                let (start, end) = (start + offset, end + offset);
+                canvas.draw_line(
+                    self.field_point((start.x as i32, start.y as i32)),
+                    self.field_point((end.x as i32, end.y as i32)),
+                )?;
+            }
+        }
+
+        // draw bonus targets on the top wall, and a flash where one was just hit
+        for target in &self.targets {
+            let rect = Rect::new(
+                target.x as i32,
+                0,
+                Target::WIDTH as u32,
+                self.config.thickness as u32,
+            );
+            if target.active {
+                canvas.set_draw_color(Self::TARGET_COLOR);
+                canvas.fill_rect(self.field_rect(rect))?;
+            } else if target.flash > 0.0 {
+                let (r, g, b) = Self::TARGET_COLOR.rgb();
+                let alpha = 255.0 * target.flash / Target::FLASH_TIME;
+                canvas.set_blend_mode(BlendMode::Blend);
+                canvas.set_draw_color(Color::RGBA(r, g, b, alpha as u8));
+                canvas.fill_rect(self.field_rect(rect))?;
+                canvas.set_blend_mode(BlendMode::None);
+            }
+        }
+        canvas.set_draw_color(self.theme.foreground);
+
+        // draw power-ups
+        for power_up in &self.power_ups {
+            canvas.set_draw_color(power_up.kind.color());
+            canvas.fill_rect(self.field_rect(Rect::new(
+                (power_up.pos.x - self.config.thickness) as i32,
+                (power_up.pos.y - self.config.thickness / 2.0) as i32,
+                (self.config.thickness * 2.0) as u32,
+                self.config.thickness as u32,
+            )))?;
+        }
+        canvas.set_draw_color(self.theme.foreground);
+
+        if self.practice {
+            self.draw_predictions(canvas)?;
+        }
+
+        // draw ball, over a trail of its past positions fading out and shrinking with age
+        let radius = (self.config.thickness / 2.0) as i32;
+        // the colors for speed span from serving speed up to the speed the balls are capped at
+        let max_speed =
+            (self.config.ball_speed * self.config.max_speed_scale).min(physics::BALL_MAX_SPEED);
+        for i in 0..self.balls.len() {
+            let mut color = if self.speed_colors {
+                theme::speed_color(
+                    self.balls[i].vel.length(),
+                    self.config.ball_speed,
+                    max_speed,
+                )
+            } else {
+                self.balls[i].color
+            };
+            canvas.set_blend_mode(BlendMode::Blend);
+            for age in (0..self.balls[i].trail.len()).rev() {
+                let pos = self.balls[i].trail[age];
+                let fade = 1.0 - (age + 1) as f32 / (Ball::TRAIL_LENGTH + 1) as f32;
+                color.a = (Self::TRAIL_ALPHA as f32 * fade) as u8;
+                canvas.set_draw_color(color);
+                self.draw_circle(
+                    canvas,
+                    pos.x as i32,
+                    pos.y as i32,
+                    (radius as f32 * fade) as i32,
+                )?;
+            }
+            canvas.set_blend_mode(BlendMode::None);
+
+            let pos = self.balls[i].pos;
+            color.a = u8::MAX;
+            canvas.set_draw_color(color);
+            self.draw_circle(canvas, pos.x as i32, pos.y as i32, radius)?;
+        }
+        canvas.set_draw_color(self.theme.foreground);
+
+        if self.night {
+            self.draw_night(canvas)?;
+        }
+
+        // draw where the next ball will drop in, blinking, just before it does
+        if matches!(self.mode, Mode::Endless | Mode::Coop)
+            && self.endless_timer <= Self::ENDLESS_TELEGRAPH_TIME
+            && (self.endless_timer * 8.0) as i32 % 2 == 0
+        {
+            let pos = self.drop_position();
+            let size = self.config.thickness as u32 * 2;
+            canvas.draw_rect(self.field_rect(Rect::new(
+                pos.x as i32 - size as i32 / 2,
+                pos.y as i32 - size as i32 / 2,
+                size,
+                size,
+            )))?;
+        }
+
+        match self.mode {
+            Mode::Solo
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Coop
+            | Mode::Campaign
+            | Mode::Boss => {
+                // draw score, which is the time lasted in the survival mode, and the best one
+                let best = self.best_score();
+                let score = if self.mode == Mode::Survival {
+                    format!("{:.1}s  Best: {best}s", self.survival_time)
+                } else {
+                    format!("{}  Best: {best}", self.score)
+                };
+                self.draw_text(
+                    canvas,
+                    &score,
+                    (self.config.thickness * 2.0) as i32,
+                    (self.config.thickness * 2.0) as i32,
+                )?;
+
+                // draw the campaign level and how far its goal has got under the score
+                if self.mode == Mode::Campaign {
+                    let level = &self.levels[self.campaign_level];
+                    let goal = format!(
+                        "Level {}: {}  {}",
+                        self.campaign_level + 1,
+                        level.name,
+                        level.goal.progress(self.level_time, self.level_hits)
+                    );
+                    self.draw_text(
+                        canvas,
+                        &goal,
+                        (self.config.thickness * 2.0) as i32,
+                        self.config.thickness as i32 * 2 + self.font()?.height(),
+                    )?;
+                }
+
+                // draw the date of the daily challenge under the score
+                if let Some(daily) = &self.daily {
+                    let date = format!("Daily challenge {}", daily.date);
+                    self.draw_text(
+                        canvas,
+                        &date,
+                        (self.config.thickness * 2.0) as i32,
+                        self.config.thickness as i32 * 2 + self.font()?.height(),
+                    )?;
+                }
+
+                // draw the time left large in the center in place of the lives
+                if self.mode == Mode::TimeAttack {
+                    self.draw_countdown(canvas)?;
+                }
+
+                // draw remaining lives
+                let lives = if self.mode == Mode::TimeAttack {
+                    0
+                } else {
+                    self.lives
+                };
+                for i in 0..lives {
+                    canvas.fill_rect(Rect::new(
+                        (self.width - self.config.thickness * (3.0 + 2.0 * i as f32)) as i32,
+                        (self.config.thickness * 2.0) as i32,
+                        self.config.thickness as u32,
+                        self.config.thickness as u32,
+                    ))?;
+                }
+            }
+            Mode::Versus => {
+                // draw scores of both players on their own side
+                self.draw_text(
+                    canvas,
+                    &self.left_score.to_string(),
+                    (self.width / 4.0) as i32,
+                    (self.config.thickness * 2.0) as i32,
+                )?;
+                self.draw_text(
+                    canvas,
+                    &self.right_score.to_string(),
+                    (self.width * 3.0 / 4.0) as i32,
+                    (self.config.thickness * 2.0) as i32,
+                )?;
+
+                // show who the player is up against in the tournament under the scores
+                if let Some(tournament) = &self.tournament {
+                    let round = format!(
+                        "Round {}/{}: {}",
+                        tournament.round + 1,
+                        tournament.roster().len(),
+                        tournament.opponent().name
+                    );
+                    let (width, _) = self
+                        .font()?
+                        .size_of(&round)
+                        .map_err(|err| err.to_string())?;
+                    self.draw_text(
+                        canvas,
+                        &round,
+                        (self.width / 2.0) as i32 - width as i32 / 2,
+                        (self.config.thickness * 2.0) as i32 + self.font()?.height(),
+                    )?;
+                }
+
+                // show both handicaps under the scores whenever either player has one
+                if self.left_handicap != Handicap::NONE || self.right_handicap != Handicap::NONE {
+                    let y = (self.config.thickness * 2.0) as i32 + self.font()?.height();
+                    self.draw_text(
+                        canvas,
+                        &self.left_handicap.describe(),
+                        (self.width / 4.0) as i32,
+                        y,
+                    )?;
+                    self.draw_text(
+                        canvas,
+                        &self.right_handicap.describe(),
+                        (self.width * 3.0 / 4.0) as i32,
+                        y,
+                    )?;
+                }
+            }
+            Mode::FourPlayer => {
+                // draw the lives of each player in a row along their side
+                for side in Side::ALL {
+                    self.draw_side_lives(canvas, side)?;
+                }
+            }
+            Mode::Computer | Mode::TwoPlayer => {
+                // draw scores of the top and the bottom side on their own half
+                self.draw_text(
+                    canvas,
+                    &self.top_score.to_string(),
+                    (self.config.thickness * 2.0) as i32,
+                    (self.height / 2.0) as i32
+                        - self.font()?.height()
+                        - self.config.thickness as i32,
+                )?;
+                let score = match self.mode.high_score_name() {
+                    Some(_) => format!("{}  Best: {}", self.score, self.best_score()),
+                    None => self.score.to_string(),
+                };
+                self.draw_text(
+                    canvas,
+                    &score,
+                    (self.config.thickness * 2.0) as i32,
+                    (self.height / 2.0 + self.config.thickness) as i32,
+                )?;
+            }
+        }
+
+        // draw difficulty, current and longest rally, and how many balls are in play out of the
+        // most there can be, which there is no limit to in some modes
+        let balls = if matches!(self.mode, Mode::Juggling | Mode::Endless | Mode::Coop) {
+            self.balls.len().to_string()
+        } else {
+            format!("{}/{}", self.balls.len(), self.config.max_balls)
+        };
+        let rally = format!(
+            "{}  Rally {}  Best {}  Balls {balls}",
+            self.difficulty.name(),
+            self.rally,
+            self.longest_rally.max(self.rally)
+        );
+        let (width, _) = self
+            .font()?
+            .size_of(&rally)
+            .map_err(|err| err.to_string())?;
+        self.draw_text(
+            canvas,
+            &rally,
+            (self.width / 2.0) as i32 - width as i32 / 2,
+            (self.config.thickness * 2.0) as i32,
+        )?;
+
+        for i in 0..self.popups.len() {
+            let (width, _) = self
+                .font()?
+                .size_of(&self.popups[i].text)
+                .map_err(|err| err.to_string())?;
+            let pos = self.popups[i].pos;
+            let text = self.popups[i].text.clone();
+            self.draw_text(
+                canvas,
+                &text,
+                self.mirror_x(pos.x as i32) - width as i32 / 2,
+                pos.y as i32,
+            )?;
+        }
+
+        // announce the achievement unlocked first among the ones waiting, above the paddle
+        if let Some(achievement) = self.unlocked.front() {
+            let text = format!("Achievement unlocked! {}", achievement.name());
+            let (width, _) = self.font()?.size_of(&text).map_err(|err| err.to_string())?;
+            self.draw_text(
+                canvas,
+                &text,
+                (self.width / 2.0) as i32 - width as i32 / 2,
+                (self.height - self.config.thickness * 4.0) as i32 - self.font()?.height() * 2,
+            )?;
+        }
+
+        // warn in the middle of the field that gravity is about to switch walls
+        if let Some(gravity) = &self.gravity {
+            if gravity.is_shifting() && self.state == GameState::Playing {
+                let text = format!("Gravity shifting {}!", gravity.next.name());
+                let (width, _) = self.font()?.size_of(&text).map_err(|err| err.to_string())?;
+                self.draw_text(
+                    canvas,
+                    &text,
+                    (self.width / 2.0) as i32 - width as i32 / 2,
+                    (self.height / 2.0) as i32 - self.font()?.height() / 2,
+                )?;
+            }
+        }
+
+        if self.slow_motion_remaining > 0.0 {
+            let (width, _) = self
+                .font()?
+                .size_of("Slow motion")
+                .map_err(|err| err.to_string())?;
+            self.draw_text(
+                canvas,
+                "Slow motion",
+                (self.width / 2.0) as i32 - width as i32 / 2,
+                self.config.thickness as i32 * 2 + self.font()?.height(),
+            )?;
+        }
+
+        if self.sudden_death {
+            let (width, _) = self
+                .font()?
+                .size_of("Sudden death")
+                .map_err(|err| err.to_string())?;
+            self.draw_text(
+                canvas,
+                "Sudden death",
+                (self.width / 2.0) as i32 - width as i32 / 2,
+                self.config.thickness as i32 * 2 + self.font()?.height(),
+            )?;
+        }
+        if self.sudden_death_banner > 0.0 && self.state == GameState::Playing {
+            self.draw_overlay(
+                canvas,
+                &[
+                    "Sudden death!",
+                    "Smaller paddles, one ball",
+                    "Next point wins",
+                ],
+            )?;
+        }
+
+        if self.debug_draw {
+            self.draw_debug(canvas)?;
+        }
+
+        if self.show_fps {
+            self.draw_text(
+                canvas,
+                &format!("{:.0} FPS", self.fps),
+                (self.config.thickness * 2.0) as i32,
+                (self.height - self.config.thickness * 3.0) as i32 - self.font()?.height(),
+            )?;
+        }
+
+        // show that the controls are swapped in the opposite corner to the frame rate
+        if self.mirror {
+            let (width, _) = self
+                .font()?
+                .size_of("Mirror")
+                .map_err(|err| err.to_string())?;
+            self.draw_text(
+                canvas,
+                "Mirror",
+                (self.width - self.config.thickness * 2.0) as i32 - width as i32,
+                (self.height - self.config.thickness * 3.0) as i32 - self.font()?.height(),
+            )?;
+        }
+
+        if self.demo {
+            // leave the field in view, with the prompt twice the size of the rest of the text
+            let y = (self.height / 2.0) as i32 - self.font()?.height();
+            self.draw_large_text(canvas, "Press any key", self.theme.foreground, 2, y)?;
+        } else if let GameState::Menu(selected) = self.state {
+            self.draw_menu(canvas, selected)?;
+        } else if self.state == GameState::GameOver && self.mode == Mode::Survival {
+            let survived = format!(
+                "Survived {:.1}s  Best {:.1}s",
+                self.survival_time, self.best_survival_time
+            );
+            self.draw_overlay(canvas, &["Game Over", &survived, "Press R to restart"])?;
+        } else if self.state == GameState::GameOver && self.mode == Mode::Juggling {
+            let juggled = format!("Score {}  Most balls {}", self.score, self.peak_balls);
+            self.draw_overlay(canvas, &["Game Over", &juggled, "Press R to restart"])?;
+        } else if self.state == GameState::TimeUp {
+            let scored = format!("Score {}  Best {}", self.score, self.best_score());
+            self.draw_overlay(canvas, &["Time's up!", &scored, "Press R to restart"])?;
+        } else if self.state == GameState::GameOver {
+            self.draw_overlay(canvas, &["Game Over", "Press R to restart"])?;
+        } else if self.tournament.is_some()
+            && matches!(self.state, GameState::Bracket | GameState::Won(_))
+        {
+            self.draw_tournament(canvas)?;
+        } else if let GameState::Won(winner) = self.state {
+            self.draw_overlay(
+                canvas,
+                &[winner.message(), "Press R to restart or Esc to quit"],
+            )?;
+        } else if self.is_paused && self.debug {
+            // keep the field in plain view for inspecting it step by step
+            let text = "Paused  Space: resume  .: step";
+            let (width, _) = self.font()?.size_of(text).map_err(|err| err.to_string())?;
+            self.draw_text(
+                canvas,
+                text,
+                (self.width / 2.0) as i32 - width as i32 / 2,
+                (self.height - self.config.thickness * 3.0) as i32 - self.font()?.height(),
+            )?;
+        } else if self.is_paused {
+            self.draw_overlay(canvas, &["Paused", "Press P to resume"])?;
+        } else if let GameState::Countdown(remaining) = self.state {
+            // four times the size of the rest of the text, in the middle of the field
+            let text = (remaining.ceil() as u32).to_string();
+            let y = (self.height / 2.0) as i32 - self.font()?.height() * 2;
+            self.draw_large_text(canvas, &text, self.theme.foreground, 4, y)?;
+        }
+
+        canvas.present();
+        Ok(())
+    }
+
+    // draw a line from the center of each ball along its velocity, as long as the ball travels
+    // in a fixed time, and outline the collision box of each paddle in play
+    fn draw_debug(&mut self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+        canvas.set_draw_color(Color::RGB(255, 0, 0));
+        for ball in &self.balls {
+            let tip = ball.pos + ball.vel * Self::DEBUG_VELOCITY_SCALE;
+            canvas.draw_line(
+                self.field_point((ball.pos.x as i32, ball.pos.y as i32)),
+                self.field_point((tip.x as i32, tip.y as i32)),
+            )?;
+        }
+
+        let rects: Vec<Rect> = self.paddles().iter().map(|paddle| paddle.rect()).collect();
+        canvas.set_draw_color(Color::RGB(0, 255, 0));
+        for rect in rects {
+            canvas.draw_rect(self.field_rect(rect))?;
+        }
+        canvas.set_draw_color(self.theme.foreground);
+        Ok(())
+    }
+
+    // draw the path each ball in flight would take off the walls alone, in its own color
+    fn draw_predictions(&mut self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+        let bounds = self.wall_bounds();
+        canvas.set_blend_mode(BlendMode::Blend);
+        for ball in self.balls.iter().filter(|ball| ball.attached.is_none()) {
+            let path: Vec<Point> =
+                physics::predict_path(ball, bounds, Self::PREDICTION_TIME, Self::UPDATE_STEP)
+                    .into_iter()
+                    .map(|pos| Point::from(self.field_point((pos.x as i32, pos.y as i32))))
+                    .collect();
+            let mut color = ball.color;
+            color.a = Self::PREDICTION_ALPHA;
+            canvas.set_draw_color(color);
+            canvas.draw_lines(path.as_slice())?;
+        }
+        canvas.set_blend_mode(BlendMode::None);
+        canvas.set_draw_color(self.theme.foreground);
+        Ok(())
+    }
+
+    // get the walls the balls bounce off in the mode, leaving the goals and the sides of players
+    // who are still in open
+    fn wall_bounds(&self) -> Bounds {
+        let thickness = self.config.thickness;
+        let (left, right) = (thickness, self.width - thickness);
+        let (top, bottom) = (thickness, self.height - thickness);
+        // the side of a player who is out is closed off by a wall
+        let wall = |side: Side, wall: f32, open: f32| {
+            if self.side_lives[side as usize] == 0 {
+                wall
+            } else {
+                open
+            }
+        };
+        match self.mode {
+            Mode::Solo
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Coop
+            | Mode::Campaign
+            | Mode::Boss => Bounds {
+                left,
+                right,
+                top,
+                bottom: f32::INFINITY,
+            },
+            Mode::Versus => Bounds {
+                left: f32::NEG_INFINITY,
+                right: f32::INFINITY,
+                top,
+                bottom,
+            },
+            Mode::Computer | Mode::TwoPlayer => Bounds {
+                left,
+                right,
+                top: f32::NEG_INFINITY,
+                bottom: f32::INFINITY,
+            },
+            Mode::FourPlayer => Bounds {
+                left: wall(Side::Left, left, f32::NEG_INFINITY),
+                right: wall(Side::Right, right, f32::INFINITY),
+                top: wall(Side::Top, top, f32::NEG_INFINITY),
+                bottom: wall(Side::Bottom, bottom, f32::INFINITY),
+            },
+        }
+    }
+
+    fn paddles(&self) -> Vec<&Paddle> {
+        match self.mode {
+            Mode::Solo
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Campaign
+            | Mode::Boss => {
+                vec![&self.paddle]
+            }
+            Mode::Coop => vec![&self.paddle, &self.partner_paddle],
+            Mode::Computer | Mode::TwoPlayer => vec![&self.paddle, &self.top_paddle],
+            Mode::FourPlayer => vec![
+                &self.paddle,
+                &self.top_paddle,
+                &self.left_paddle,
+                &self.right_paddle,
+            ],
+            Mode::Versus => vec![&self.left_paddle, &self.right_paddle],
+        }
+    }
+
+    // darken the field in cells, leaving a circle of light around each ball and a band of light
+    // around each paddle that fade out towards their edges
+    fn draw_night(&mut self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+        let radius = self.config.night_light_radius;
+        // balls light up from a point and paddles from all of their collision box, given as
+        // left, top, right and bottom
+        let mut lights: Vec<[f32; 4]> = self
+            .balls
+            .iter()
+            .map(|ball| [ball.pos.x, ball.pos.y, ball.pos.x, ball.pos.y])
+            .collect();
+        for rect in self.paddles().iter().map(|paddle| paddle.rect()) {
+            lights.push([
+                rect.left() as f32,
+                rect.top() as f32,
+                rect.right() as f32,
+                rect.bottom() as f32,
+            ]);
+        }
+        let alpha = |x: f32, y: f32| -> u8 {
+            let distance = lights
+                .iter()
+                .map(|[left, top, right, bottom]| {
+                    let dx = (left - x).max(x - right).max(0.0);
+                    let dy = (top - y).max(y - bottom).max(0.0);
+                    dx * dx + dy * dy
+                })
+                .fold(f32::INFINITY, f32::min)
+                .sqrt();
+            let fade = (radius - distance) / (radius * Self::NIGHT_FADE);
+            (Self::NIGHT_DARKNESS as f32 * (1.0 - fade.clamp(0.0, 1.0))) as u8
+        };
+
+        // cells in a row with the same darkness are drawn as one rectangle, so that most of the
+        // field takes a handful of rectangles
+        let cell = Self::NIGHT_CELL_SIZE;
+        let columns = (self.width / cell as f32).ceil() as i32;
+        let rows = (self.height / cell as f32).ceil() as i32;
+        canvas.set_blend_mode(BlendMode::Blend);
+        for row in 0..rows {
+            let y = row * cell as i32;
+            let center_y = y as f32 + cell as f32 / 2.0;
+            let mut start = 0;
+            let mut run_alpha = alpha(cell as f32 / 2.0, center_y);
+            for column in 1..=columns {
+                // there is no next cell past the last one, which ends the last run
+                let next_alpha = (column < columns)
+                    .then(|| alpha((column as f32 + 0.5) * cell as f32, center_y));
+                if next_alpha == Some(run_alpha) {
+                    continue;
+                }
+                if run_alpha > 0 {
+                    let mut color = self.theme.background;
+                    color.a = run_alpha;
+                    canvas.set_draw_color(color);
+                    canvas.fill_rect(self.field_rect(Rect::new(
+                        start * cell as i32,
+                        y,
+                        (column - start) as u32 * cell,
+                        cell,
+                    )))?;
+                }
+                start = column;
+                run_alpha = next_alpha.unwrap_or(0);
+            }
+        }
+        canvas.set_blend_mode(BlendMode::None);
+        canvas.set_draw_color(self.theme.foreground);
+        Ok(())
+    }
+
+    // draw the seconds left on the clock at the top center, in red for the last ten
+    fn draw_countdown(&mut self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+        let text = format!("{:.1}", self.time_remaining);
+        let color = if self.time_remaining <= 10.0 {
+            Color::RGB(255, 80, 80)
+        } else {
+            self.theme.foreground
+        };
+        // twice the size of the rest of the text
+        self.draw_large_text(
+            canvas,
+            &text,
+            color,
+            2,
+            (self.config.thickness * 2.0) as i32,
+        )
+    }
+
+    // draw text `scale` times the size of the rest, centered across the field with its top at y
+    fn draw_large_text(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        color: Color,
+        scale: u32,
+        y: i32,
+    ) -> Result<(), String> {
+        let surface = self
+            .font()?
+            .render(text)
+            .blended(color)
+            .map_err(|err| err.to_string())?;
+        let texture_creator = canvas.texture_creator();
+        let texture = texture_creator
+            .create_texture_from_surface(&surface)
+            .map_err(|err| err.to_string())?;
+        let (width, height) = (surface.width() * scale, surface.height() * scale);
+        canvas.copy(
+            &texture,
+            None,
+            Rect::new(
+                (self.width / 2.0) as i32 - width as i32 / 2,
+                y,
+                width,
+                height,
+            ),
+        )?;
+        Ok(())
+    }
+
+    // draw the title over the field, with the options of the menu under it and an arrow by the
+    // one picked
+    fn draw_menu(&mut self, canvas: &mut Canvas<Window>, selected: MenuItem) -> Result<(), String> {
+        let mut lines: Vec<String> = MenuItem::ALL
+            .into_iter()
+            .map(|item| {
+                let arrow = if item == selected { ">" } else { " " };
+                format!("{arrow} {:<5}", item.label())
+            })
+            .collect();
+        lines.push(String::new());
+        lines.push("Up/Down to choose, Enter to confirm".to_string());
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        self.draw_overlay(canvas, &lines)?;
+
+        // three times the size of the rest of the text, above the options
+        let y = (self.height / 2.0) as i32 - self.font()?.height() * 6;
+        self.draw_large_text(canvas, "PONG", self.theme.foreground, 3, y)
+    }
+
+    // draw the bracket of the tournament between matches, with the opponents beaten so far and
+    // the one up next, or how the run went once it is over
+    fn draw_tournament(&mut self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+        let Some(tournament) = &self.tournament else {
+            return Ok(());
+        };
+        let rounds = tournament.roster().len();
+        let mut lines = match self.state {
+            GameState::Won(Winner::LeftPlayer) => vec![
+                "Tournament champion!".to_string(),
+                format!("Beat all {rounds} opponents"),
+            ],
+            GameState::Won(_) => vec![
+                format!("Knocked out by {}", tournament.opponent().name),
+                format!("Reached round {} of {rounds}", tournament.round + 1),
+            ],
+            _ => {
+                let mut lines = vec![format!("Round {} won!", tournament.round)];
+                for (round, opponent) in tournament.roster().iter().enumerate() {
+                    let progress = match round.cmp(&tournament.round) {
+                        Ordering::Less => "beaten",
+                        Ordering::Equal => "next",
+                        Ordering::Greater => "",
+                    };
+                    lines.push(format!("{}. {:<10}{progress:>6}", round + 1, opponent.name));
+                }
+                lines
+            }
+        };
+        lines.push(match self.state {
+            GameState::Bracket => "Press Space for the next match".to_string(),
+            _ => "Press R to restart or Esc to quit".to_string(),
+        });
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        self.draw_overlay(canvas, &lines)
+    }
+
+    // darken the whole field and show lines of text in the center of it
+    fn draw_overlay(&mut self, canvas: &mut Canvas<Window>, lines: &[&str]) -> Result<(), String> {
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, 160));
+        canvas.fill_rect(None)?;
+        canvas.set_blend_mode(BlendMode::None);
+
+        let line_height = self.font()?.height();
+        let mut y = (self.height / 2.0) as i32 - line_height * lines.len() as i32 / 2;
+        for line in lines {
+            let (width, _) = self.font()?.size_of(line).map_err(|err| err.to_string())?;
+            self.draw_text(
+                canvas,
+                line,
+                (self.width / 2.0) as i32 - width as i32 / 2,
+                y,
+            )?;
+            y += line_height;
+        }
+        Ok(())
+    }
+
+    // get where an x coordinate on the field is drawn, which is the other side of the field in
+    // the mirrored view, so that everything on the field is drawn through here
+    fn mirror_x(&self, x: i32) -> i32 {
+        if self.mirror_view {
+            self.width as i32 - x
+        } else {
+            x
+        }
+    }
+
+    // get where a rect or a point on the field is drawn, mirrored and shaken along with the rest
+    // of the field
+    fn field_rect(&self, rect: Rect) -> Rect {
+        let left = self.mirror_x(rect.left()).min(self.mirror_x(rect.right()));
+        let (dx, dy) = self.shake_offset;
+        Rect::new(left + dx, rect.y() + dy, rect.width(), rect.height())
+    }
+
+    fn field_point(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        let (dx, dy) = self.shake_offset;
+        (self.mirror_x(x) + dx, y + dy)
+    }
+
+    // draw a dashed line across the middle of the field between the two sides, if there are two
+    fn draw_net(&mut self, canvas: &mut Canvas<Window>) -> Result<(), String> {
+        let vertical = match self.mode {
+            // the co-op players' halves are split the same way as the versus players' sides
+            Mode::Versus | Mode::Coop => true,
+            Mode::Computer | Mode::TwoPlayer => false,
+            Mode::Solo
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Campaign
+            | Mode::Boss
+            | Mode::FourPlayer => return Ok(()),
+        };
+        let length = if vertical { self.height } else { self.width };
+        let dash = self.config.thickness;
+
+        // dashes and gaps of the same length, centered so that both ends look the same
+        let count = ((length / dash - 1.0) / 2.0).floor().max(0.0) as i32 + 1;
+        let start = (length - (2 * count - 1) as f32 * dash) / 2.0;
+        for i in 0..count {
+            let along = start + (2 * i) as f32 * dash;
+            let rect = if vertical {
+                Rect::new(
+                    ((self.width - dash / 2.0) / 2.0) as i32,
+                    along as i32,
+                    (dash / 2.0) as u32,
+                    dash as u32,
+                )
+            } else {
+                Rect::new(
+                    along as i32,
+                    ((self.height - dash / 2.0) / 2.0) as i32,
+                    dash as u32,
+                    (dash / 2.0) as u32,
+                )
+            };
+            canvas.fill_rect(self.field_rect(rect))?;
+        }
+        Ok(())
+    }
+
+    // fill a circle one horizontal line at a time
+    fn draw_circle(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        cx: i32,
+        cy: i32,
+        radius: i32,
+    ) -> Result<(), String> {
+        for dy in -radius..=radius {
+            let dx = ((radius * radius - dy * dy) as f32).sqrt() as i32;
+            canvas.fill_rect(self.field_rect(Rect::new(
+                cx - dx,
+                cy + dy,
+                (2 * dx + 1) as u32,
+                1,
+            )))?;
+        }
+        Ok(())
+    }
+
+    fn side_paddle(&self, side: Side) -> &Paddle {
+        match side {
+            Side::Bottom => &self.paddle,
+            Side::Top => &self.top_paddle,
+            Side::Left => &self.left_paddle,
+            Side::Right => &self.right_paddle,
+        }
+    }
+
+    // get the wall closing off a side
+    fn side_wall(&self, side: Side) -> Rect {
+        let thickness = self.config.thickness as u32;
+        let (width, height) = (self.width as u32, self.height as u32);
+        match side {
+            Side::Bottom => Rect::new(0, (height - thickness) as i32, width, thickness),
+            Side::Top => Rect::new(0, 0, width, thickness),
+            Side::Left => Rect::new(0, 0, thickness, height),
+            Side::Right => Rect::new((width - thickness) as i32, 0, thickness, height),
+        }
+    }
+
+    // draw a square for each life of a player, in a row centered on their side a little way in
+    fn draw_side_lives(&mut self, canvas: &mut Canvas<Window>, side: Side) -> Result<(), String> {
+        let thickness = self.config.thickness;
+        let lives = self.side_lives[side as usize];
+        let inward = side.inward();
+        let along = Vector2::new(inward.y.abs(), inward.x.abs());
+        let center = Vector2::new(self.width / 2.0, self.height / 2.0);
+        let edge = center - Vector2::new(inward.x * center.x, inward.y * center.y);
+        let first = edge + inward * (thickness * 3.0) - along * (thickness * (lives as f32 - 1.0));
+
+        canvas.set_draw_color(side.color());
+        for i in 0..lives {
+            let pos = first + along * (thickness * 2.0 * i as f32);
+            canvas.fill_rect(self.field_rect(Rect::new(
+                (pos.x - thickness / 2.0) as i32,
+                (pos.y - thickness / 2.0) as i32,
+                thickness as u32,
+                thickness as u32,
+            )))?;
+        }
+        canvas.set_draw_color(self.theme.foreground);
+        Ok(())
+    }
+
+    // fill a rectangle with its short ends rounded off into half circles, only for looks as
+    // the paddles still collide as the whole rectangle
+    fn draw_capsule(&mut self, canvas: &mut Canvas<Window>, rect: Rect) -> Result<(), String> {
+        let radius = rect.width().min(rect.height()) as i32 / 2;
+        if rect.width() >= rect.height() {
+            let (left, right) = (rect.left() + radius, rect.right() - 1 - radius);
+            let cy = rect.top() + radius;
+            canvas.fill_rect(self.field_rect(Rect::new(
+                left,
+                rect.top(),
+                (right - left + 1).max(1) as u32,
+                rect.height(),
+            )))?;
+            self.draw_circle(canvas, left, cy, radius)?;
+            self.draw_circle(canvas, right, cy, radius)?;
+        } else {
+            let (top, bottom) = (rect.top() + radius, rect.bottom() - 1 - radius);
+            let cx = rect.left() + radius;
+            canvas.fill_rect(self.field_rect(Rect::new(
+                rect.left(),
+                top,
+                rect.width(),
+                (bottom - top + 1).max(1) as u32,
+            )))?;
+            self.draw_circle(canvas, cx, top, radius)?;
+            self.draw_circle(canvas, cx, bottom, radius)?;
+        }
+        Ok(())
+    }
+
+    // draw white text with its top-left corner at (x, y)
+    fn draw_text(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        text: &str,
+        x: i32,
+        y: i32,
+    ) -> Result<(), String> {
+        let surface = self
+            .font()?
+            .render(text)
+            .blended(self.theme.foreground)
+            .map_err(|err| err.to_string())?;
+        let texture_creator = canvas.texture_creator();
+        let texture = texture_creator
+            .create_texture_from_surface(&surface)
+            .map_err(|err| err.to_string())?;
+        canvas.copy(
+            &texture,
+            None,
+            Rect::new(x, y, surface.width(), surface.height()),
+        )?;
+        Ok(())
+    }
+
+    // put the balls for the start of a game on the field
+    fn spawn_initial_balls(&mut self) {
+        // spread the balls out evenly across the middle of the field, in the direction the
+        // paddles move in
+        let count = if self.mode == Mode::Campaign {
+            self.current_level().balls
+        } else {
+            self.config.initial_balls
+        };
+        let spread = |length: f32, i: usize| length * (2 * i + 1) as f32 / (2 * count) as f32;
+        let initial_positions: Vec<Vector2> = (0..count)
+            .map(|i| match self.mode {
+                Mode::Solo
+                | Mode::Computer
+                | Mode::TwoPlayer
+                | Mode::Breakout
+                | Mode::Survival
+                | Mode::Juggling
+                | Mode::TimeAttack
+                | Mode::Endless
+                | Mode::Coop
+                | Mode::Campaign
+                | Mode::Boss
+                | Mode::FourPlayer => Vector2::new(spread(self.width, i), self.height / 2.0),
+                Mode::Versus => Vector2::new(self.width / 2.0, spread(self.height, i)),
+            })
+            .collect();
+        for pos in initial_positions {
+            let vel = self.get_random_velocity(BallKind::Normal);
+            let color = self.next_ball_color(BallKind::Normal);
+            self.balls.push_front(Ball {
+                pos,
+                vel,
+                kind: BallKind::Normal,
+                color,
+                attached: None,
+                stuck: false,
+                speed_scale: 1.0,
+                portal_cooldown: 0.0,
+                spin: 0.0,
+                trail: VecDeque::new(),
+            });
+        }
+    }
+
+    fn initial_bricks(&self) -> Vec<Brick> {
+        if self.mode == Mode::Breakout {
+            Brick::layout(0, self.width, self.height, self.config.thickness)
+        } else {
+            Vec::new()
+        }
+    }
+
+    // start a new game from the beginning
+    fn reset(&mut self) {
+        // replay the daily challenge from the same seed so that it plays out the same way
+        if let Some(daily) = &self.daily {
+            self.rng = StdRng::seed_from_u64(daily.seed);
+        }
+        self.end_rally();
+        self.score = 0;
+        self.misses = 0;
+        self.left_score = self.left_handicap.head_start;
+        self.right_score = self.right_handicap.head_start;
+        self.top_score = 0;
+        self.exit_sudden_death();
+        if self.gravity.is_some() {
+            self.gravity = Some(GravityShift::new(&mut self.rng));
+        }
+        if self.boss.is_some() {
+            self.boss = Some(Boss::new(self.width, self.config.thickness));
+        }
+        if self.tournament.is_some() {
+            self.tournament = Some(Tournament::start(self.height / 2.0));
+            self.face_opponent();
+        }
+        self.hazards.clear();
+        self.lives = self.initial_lives;
+        self.side_lives = [self.initial_lives; 4];
+        self.power_ups.clear();
+        self.power_up_timer = Self::POWER_UP_INTERVAL;
+        self.paddle_effects.clear();
+        self.slow_motion_remaining = 0.0;
+        self.sticky_remaining = 0.0;
+        self.shield = false;
+        self.shield_flash = 0.0;
+        self.dash_remaining = 0.0;
+        self.dash_cooldown = 0.0;
+        self.paddle_base_width = self.config.paddle_width;
+        self.paddle.width = self.config.paddle_width;
+        self.survival_time = 0.0;
+        self.juggling_points = 0.0;
+        self.juggling_timer = Self::JUGGLING_INTERVAL;
+        self.peak_balls = 0;
+        self.endless_timer = Self::ENDLESS_START_INTERVAL;
+        self.endless_interval = Self::ENDLESS_START_INTERVAL;
+        self.spawn_timer = 0.0;
+        self.stats = Stats::default();
+        self.place_targets();
+        self.popups.clear();
+        self.time_remaining = Self::TIME_ATTACK_TIME;
+        self.level = 0;
+        self.bricks = self.initial_bricks();
+        self.balls.clear();
+        self.ball_colors = 0;
+        if self.mode == Mode::Campaign {
+            self.start_level(0);
+        } else {
+            self.spawn_initial_balls();
+        }
+        self.state = GameState::Countdown(Self::COUNTDOWN_TIME);
+    }
+
+    // put a new ball into play, sitting on the player's paddle until it is launched if there is
+    // one, or in the center of the field otherwise
+    fn serve_ball(&mut self, kind: BallKind) {
+        let color = self.next_ball_color(kind);
+        let ball = match self.mode {
+            Mode::Solo
+            | Mode::Computer
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Coop
+            | Mode::Campaign
+            | Mode::Boss => Ball {
+                pos: Vector2 {
+                    x: self.paddle.pos.x,
+                    y: self.paddle.pos.y - self.config.thickness / 2.0,
+                },
+                vel: Vector2 { x: 0.0, y: 0.0 },
+                kind,
+                color,
+                attached: Some(0.0),
+                stuck: false,
+                speed_scale: 1.0,
+                portal_cooldown: 0.0,
+                spin: 0.0,
+                trail: VecDeque::new(),
+            },
+            Mode::Versus | Mode::TwoPlayer | Mode::FourPlayer => {
+                // a serve after a goal is counted down like the first one, giving the players
+                // time to get ready
+                if self.balls.is_empty() && self.state == GameState::Playing {
+                    self.state = GameState::Countdown(Self::COUNTDOWN_TIME);
+                }
+                Ball {
+                    pos: Vector2 {
+                        x: self.width / 2.0,
+                        y: self.height / 2.0,
+                    },
+                    vel: self.get_random_velocity(kind),
+                    kind,
+                    color,
+                    attached: None,
+                    stuck: false,
+                    speed_scale: 1.0,
+                    portal_cooldown: 0.0,
+                    spin: 0.0,
+                    trail: VecDeque::new(),
+                }
+            }
+        };
+        self.balls.push_front(ball);
+    }
+
+    // launch all the balls sitting on the player's paddle, angled towards where it is moving
+    fn launch_balls(&mut self) {
+        let (along, away) = physics::bounce_velocity(
+            self.config.ball_speed,
+            self.paddle.dir as f32 * Self::LAUNCH_OFFSET,
+        );
+        for ball in &mut self.balls {
+            if ball.attached.is_some() && !ball.stuck {
+                ball.attached = None;
+                ball.vel = Vector2 { x: along, y: -away } * ball.kind.speed();
+            }
+        }
+        self.release_stuck_balls();
+    }
+
+    // let the balls caught by the sticky paddle go straight up, carried along by the paddle's
+    // movement
+    fn release_stuck_balls(&mut self) {
+        let paddle_vel = self.paddle.vel();
+        for ball in &mut self.balls {
+            if ball.stuck {
+                ball.attached = None;
+                ball.stuck = false;
+                ball.vel =
+                    Vector2::new(0.0, -self.config.ball_speed * ball.kind.speed()) + paddle_vel;
+            }
+        }
+    }
+
+    // look for a bundled asset next to the executable, falling back to the source tree
+    // so that `cargo run` works as well
+    fn asset_path(name: &str) -> PathBuf {
+        if let Some(dir) = env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        {
+            let path = dir.join("assets").join(name);
+            if path.exists() {
+                return path;
+            }
+        }
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join(name)
+    }
+
+    // lay out the obstacles for the field, leaving out any that would get in the way of a paddle
+    fn place_obstacles(
+        layout: Layout,
+        mode: Mode,
+        width: f32,
+        height: f32,
+        config: &GameConfig,
+    ) -> Vec<Obstacle> {
+        let thickness = config.thickness;
+        let paddle_areas = match mode {
+            Mode::Solo
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Coop
+            | Mode::Campaign
+            | Mode::Boss => {
+                vec![(
+                    Vector2::new(0.0, height - thickness * 2.0),
+                    Vector2::new(width, thickness * 2.0),
+                )]
+            }
+            Mode::Computer | Mode::TwoPlayer => vec![
+                (Vector2::new(0.0, 0.0), Vector2::new(width, thickness * 2.0)),
+                (
+                    Vector2::new(0.0, height - thickness * 2.0),
+                    Vector2::new(width, thickness * 2.0),
+                ),
+            ],
+            Mode::Versus => vec![
+                (
+                    Vector2::new(0.0, 0.0),
+                    Vector2::new(thickness * 2.0, height),
+                ),
+                (
+                    Vector2::new(width - thickness * 2.0, 0.0),
+                    Vector2::new(thickness * 2.0, height),
+                ),
+            ],
+            Mode::FourPlayer => vec![
+                (Vector2::new(0.0, 0.0), Vector2::new(width, thickness * 2.0)),
+                (
+                    Vector2::new(0.0, height - thickness * 2.0),
+                    Vector2::new(width, thickness * 2.0),
+                ),
+                (
+                    Vector2::new(0.0, 0.0),
+                    Vector2::new(thickness * 2.0, height),
+                ),
+                (
+                    Vector2::new(width - thickness * 2.0, 0.0),
+                    Vector2::new(thickness * 2.0, height),
+                ),
+            ],
+        };
+
+        layout
+            .obstacles(width, height, thickness)
+            .into_iter()
+            .filter(|obstacle| {
+                !paddle_areas
+                    .iter()
+                    .any(|&(pos, size)| obstacle.overlaps(pos, size))
+            })
+            .collect()
+    }
+
+    // get appropriate random velocity for a ball of `kind` being served
+    fn get_random_velocity(&mut self, kind: BallKind) -> Vector2 {
+        let mut sp_x = self.rng.gen_range(0..400) as f32;
+        let sp_y = self.rng.gen_range(-400..-200) as f32;
+
+        if sp_x < 200.0 {
+            sp_x = -(sp_x + 200.0);
+        }
+
+        let vel = Vector2::new(sp_x, sp_y).normalize() * (self.config.ball_speed * kind.speed());
+
+        match self.mode {
+            Mode::Solo
+            | Mode::Breakout
+            | Mode::Survival
+            | Mode::Juggling
+            | Mode::TimeAttack
+            | Mode::Endless
+            | Mode::Coop
+            | Mode::Campaign
+            | Mode::Boss => vel,
+            // serve towards either the top or the bottom paddle
+            Mode::Computer | Mode::TwoPlayer | Mode::FourPlayer => {
+                Vector2::new(vel.x, if self.rng.gen() { vel.y } else { -vel.y })
+            }
+            // serve sideways towards either of the players
+            Mode::Versus => Vector2::new(if self.rng.gen() { vel.y } else { -vel.y }, vel.x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a solo game that is only simulated, so that it doesn't touch the player's saved scores,
+    // already being played and with no balls in it yet
+    fn solo_game() -> Game {
+        let args = Args {
+            headless: Some(0),
+            ..Args::default()
+        };
+        let mut game = Game::build(&args).expect("failed to build a headless game");
+        game.demo = false;
+        game.balls.clear();
+        game.state = GameState::Playing;
+        game
+    }
+
+    fn run(game: &mut Game, seconds: f32) {
+        for _ in 0..(seconds / Game::UPDATE_STEP) as u32 {
+            game.update(Game::UPDATE_STEP);
+        }
+    }
+
+    #[test]
+    fn ball_hit_by_paddle_scores() {
+        let mut game = solo_game();
+        // off the middle of the paddle, so that it isn't a perfect hit
+        let x = game.paddle.pos.x + game.paddle.width / 4.0;
+        let y = game.paddle.pos.y - 30.0;
+        game.balls
+            .push_back(Ball::at(Vector2::new(x, y), Vector2::new(0.0, 300.0)));
+        run(&mut game, 0.3);
+        assert_eq!(game.score(), 1);
+        assert_eq!(game.misses(), 0);
+    }
 
-        Vector2 { x: sp_x, y: sp_y }
+    #[test]
+    fn ball_past_paddle_is_missed_once() {
+        let mut game = solo_game();
+        let x = game.paddle.pos.x + game.paddle.width * 2.0;
+        let y = game.paddle.pos.y - 30.0;
+        game.balls
+            .push_back(Ball::at(Vector2::new(x, y), Vector2::new(0.0, 300.0)));
+        run(&mut game, 1.0);
+        assert_eq!(game.score(), 0);
+        assert_eq!(game.misses(), 1);
     }
 }
@@ -0,0 +1,78 @@
+use sdl2::controller::{Axis, Button, GameController};
+use sdl2::event::Event;
+use sdl2::{GameControllerSubsystem, Sdl};
+
+// a controller steering the player's paddle, kept together with the subsystem that has to stay
+// open to use it, and picked up or let go as it is plugged in or out
+pub struct Gamepad {
+    subsystem: GameControllerSubsystem,
+    controller: Option<GameController>,
+    // position of the left stick along its x-axis
+    stick: i16,
+}
+
+impl Gamepad {
+    // how far the stick has to be pushed before it counts, so that a stick resting slightly off
+    // center doesn't move the paddle
+    const DEAD_ZONE: i16 = 8000;
+
+    pub fn open(sdl_context: &Sdl) -> Result<Gamepad, String> {
+        let subsystem = sdl_context
+            .game_controller()
+            .map_err(|err| format!("Failed to initialize SDL2 game controller subsystem: {err}"))?;
+        let count = subsystem.num_joysticks()?;
+        let controller = (0..count)
+            .filter(|&index| subsystem.is_game_controller(index))
+            .find_map(|index| subsystem.open(index).ok());
+        Ok(Gamepad {
+            subsystem,
+            controller,
+            stick: 0,
+        })
+    }
+
+    // keep up with controllers being plugged in and out, and with the stick moving
+    pub fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::ControllerDeviceAdded { which, .. } if self.controller.is_none() => {
+                self.controller = self.subsystem.open(which).ok();
+            }
+            Event::ControllerDeviceRemoved { which, .. }
+                if self
+                    .controller
+                    .as_ref()
+                    .is_some_and(|controller| controller.instance_id() == which) =>
+            {
+                self.controller = None;
+                self.stick = 0;
+            }
+            Event::ControllerAxisMotion {
+                axis: Axis::LeftX,
+                value,
+                ..
+            } => self.stick = value,
+            _ => {}
+        }
+    }
+
+    // whether a stick at `value` along an axis is pushed far enough to count
+    pub fn is_pushed(value: i16) -> bool {
+        value.unsigned_abs() > Self::DEAD_ZONE.unsigned_abs()
+    }
+
+    // get the way the paddle is steered, from the D-pad or else from the stick
+    pub fn dir(&self) -> i32 {
+        let Some(controller) = &self.controller else {
+            return 0;
+        };
+        if controller.button(Button::DPadLeft) {
+            -1
+        } else if controller.button(Button::DPadRight) {
+            1
+        } else if Self::is_pushed(self.stick) {
+            i32::from(self.stick.signum())
+        } else {
+            0
+        }
+    }
+}
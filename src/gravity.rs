@@ -0,0 +1,92 @@
+use rand::Rng;
+
+use crate::vector::Vector2;
+
+// the wall gravity pulls the balls towards
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Down,
+    Up,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Down,
+        Direction::Up,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::Down => "down",
+            Direction::Up => "up",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+
+    fn vector(self) -> Vector2 {
+        match self {
+            Direction::Down => Vector2::new(0.0, 1.0),
+            Direction::Up => Vector2::new(0.0, -1.0),
+            Direction::Left => Vector2::new(-1.0, 0.0),
+            Direction::Right => Vector2::new(1.0, 0.0),
+        }
+    }
+
+    // pick a wall at random, other than `current`
+    fn random_other(current: Direction, rng: &mut impl Rng) -> Direction {
+        let others: Vec<Direction> = Direction::ALL
+            .into_iter()
+            .filter(|&direction| direction != current)
+            .collect();
+        others[rng.gen_range(0..others.len())]
+    }
+}
+
+// gravity over the whole field that switches to pull towards another wall every so often, with
+// the next wall known a moment ahead so that it can be warned about
+pub struct GravityShift {
+    pub direction: Direction,
+    pub next: Direction,
+    // seconds until gravity switches to the next wall
+    pub timer: f32,
+}
+
+impl GravityShift {
+    const INTERVAL: f32 = 10.0;
+    const WARNING_TIME: f32 = 1.0;
+    // acceleration of the balls in pixels per second squared
+    const STRENGTH: f32 = 250.0;
+
+    pub fn new(rng: &mut impl Rng) -> GravityShift {
+        let direction = Direction::ALL[rng.gen_range(0..Direction::ALL.len())];
+        GravityShift {
+            direction,
+            next: Direction::random_other(direction, rng),
+            timer: Self::INTERVAL,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32, rng: &mut impl Rng) {
+        self.timer -= delta_time;
+        if self.timer <= 0.0 {
+            self.direction = self.next;
+            self.next = Direction::random_other(self.direction, rng);
+            self.timer += Self::INTERVAL;
+        }
+    }
+
+    // whether gravity is about to switch, to warn the player
+    pub fn is_shifting(&self) -> bool {
+        self.timer <= Self::WARNING_TIME
+    }
+
+    // speed a ball up towards the wall for `delta_time`
+    pub fn pull(&self, vel: &mut Vector2, delta_time: f32) {
+        *vel += self.direction.vector() * (Self::STRENGTH * delta_time);
+    }
+}
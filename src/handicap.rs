@@ -0,0 +1,70 @@
+use std::str::FromStr;
+
+// changes to one player's paddle and score in the versus mode, to even out a match between
+// players of very different skill
+#[derive(Clone, Copy, PartialEq)]
+pub struct Handicap {
+    // how many times the configured width and top speed the paddle has
+    pub width_scale: f32,
+    pub speed_scale: f32,
+    // points the player starts every match with
+    pub head_start: u32,
+}
+
+impl Handicap {
+    pub const NONE: Handicap = Handicap {
+        width_scale: 1.0,
+        speed_scale: 1.0,
+        head_start: 0,
+    };
+
+    // the widest paddle still fits the smallest window, which leaves room for two paddle widths
+    const MIN_SCALE: f32 = 0.25;
+    const MAX_SCALE: f32 = 2.0;
+
+    // describe the handicap for the screen, e.g. "Width x1.5 Speed x0.8 +2"
+    pub fn describe(&self) -> String {
+        if *self == Handicap::NONE {
+            return String::from("No handicap");
+        }
+        format!(
+            "Width x{} Speed x{} +{}",
+            self.width_scale, self.speed_scale, self.head_start
+        )
+    }
+}
+
+impl Default for Handicap {
+    fn default() -> Self {
+        Handicap::NONE
+    }
+}
+
+// parse `<width>,<speed>,<head start>`, e.g. `1.5,0.8,2`
+impl FromStr for Handicap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+        let [width, speed, head_start] = parts[..] else {
+            return Err(format!("expected <width>,<speed>,<head start>, got {s}"));
+        };
+        let scale = |name: &str, value: &str| -> Result<f32, String> {
+            match value.parse() {
+                Ok(scale) if (Self::MIN_SCALE..=Self::MAX_SCALE).contains(&scale) => Ok(scale),
+                _ => Err(format!(
+                    "{name} must be between {} and {}, got {value}",
+                    Self::MIN_SCALE,
+                    Self::MAX_SCALE
+                )),
+            }
+        };
+        Ok(Handicap {
+            width_scale: scale("width", width)?,
+            speed_scale: scale("speed", speed)?,
+            head_start: head_start
+                .parse()
+                .map_err(|_| format!("head start must be a whole number, got {head_start}"))?,
+        })
+    }
+}
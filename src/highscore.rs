@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+// the best score of each mode, or of each day of the daily challenge, kept between runs in a small
+// file of `<key> <score>` lines, or only in memory without a file
+#[derive(Default)]
+pub struct HighScores {
+    path: Option<PathBuf>,
+    scores: BTreeMap<String, u32>,
+}
+
+impl HighScores {
+    // read the scores, treating a missing or unreadable file, and any line that doesn't make
+    // sense, as no score
+    pub fn load(name: &str) -> HighScores {
+        let path = data_path(name);
+        let scores = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| {
+                text.lines()
+                    .filter_map(|line| {
+                        let (mode, score) = line.split_once(' ')?;
+                        Some((mode.to_string(), score.trim().parse().ok()?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        HighScores { path, scores }
+    }
+
+    pub fn get(&self, mode: &str) -> u32 {
+        self.scores.get(mode).copied().unwrap_or(0)
+    }
+
+    // keep a score if it beats the best one of its mode so far, saving it right away
+    pub fn submit(&mut self, mode: &str, score: u32) -> Result<(), String> {
+        if score <= self.get(mode) {
+            return Ok(());
+        }
+        self.scores.insert(mode.to_string(), score);
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("Failed to create {}: {err}", dir.display()))?;
+        }
+        let text: String = self
+            .scores
+            .iter()
+            .map(|(mode, score)| format!("{mode} {score}\n"))
+            .collect();
+        fs::write(path, text)
+            .map_err(|err| format!("Failed to save high score to {}: {err}", path.display()))
+    }
+}
+
+// get where a file of the game's data named `name` goes, following the XDG base directory
+// convention
+pub fn data_path(name: &str) -> Option<PathBuf> {
+    let data_dir = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(data_dir.join("pong").join(name))
+}
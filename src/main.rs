@@ -1,9 +1,43 @@
+mod achievement;
+mod args;
+mod ball;
+mod boss;
+mod brick;
+mod campaign;
+mod config;
+mod daily;
+mod difficulty;
+mod error;
 mod game;
+mod gamepad;
+mod gravity;
+mod handicap;
+mod highscore;
+mod obstacle;
+mod paddle;
+mod physics;
+mod portal;
+mod sound;
+mod target;
+mod theme;
+mod tournament;
+mod vector;
+mod well;
+mod wind;
 
+use std::env;
 use std::process;
 
+use args::Args;
+use game::Game;
+
 fn main() {
-    let mut game = game::Game::build().unwrap_or_else(|err| {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        process::exit(1);
+    });
+
+    let mut game = Game::build(&args).unwrap_or_else(|err| {
         eprintln!("{err}");
         process::exit(1);
     });
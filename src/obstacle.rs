@@ -0,0 +1,257 @@
+use std::str::FromStr;
+
+use sdl2::pixels::Color;
+
+use crate::portal::{Portal, PortalPair};
+use crate::vector::Vector2;
+use crate::well::GravityWell;
+use crate::wind::WindZone;
+
+// a rectangular block in the field that balls bounce off like a wall
+pub struct Obstacle {
+    // top left corner
+    pub pos: Vector2,
+    pub size: Vector2,
+}
+
+impl Obstacle {
+    pub fn new(center_x: f32, center_y: f32, width: f32, height: f32) -> Obstacle {
+        Obstacle {
+            pos: Vector2::new(center_x - width / 2.0, center_y - height / 2.0),
+            size: Vector2::new(width, height),
+        }
+    }
+
+    // whether the block overlaps the rectangle with the top left corner at `pos`
+    pub fn overlaps(&self, pos: Vector2, size: Vector2) -> bool {
+        pos.x < self.pos.x + self.size.x
+            && self.pos.x < pos.x + size.x
+            && pos.y < self.pos.y + self.size.y
+            && self.pos.y < pos.y + size.y
+    }
+
+    // bounce a ball of `ball_size` off the face of the block it ran into, which is the one it
+    // overlaps the least, moving it back out so that it can't get stuck inside, and return
+    // whether it hit the block
+    pub fn collide(&self, ball_pos: &mut Vector2, ball_vel: &mut Vector2, ball_size: f32) -> bool {
+        let half = ball_size / 2.0;
+        let overlap_x =
+            (ball_pos.x + half - self.pos.x).min(self.pos.x + self.size.x - (ball_pos.x - half));
+        let overlap_y =
+            (ball_pos.y + half - self.pos.y).min(self.pos.y + self.size.y - (ball_pos.y - half));
+        if overlap_x <= 0.0 || overlap_y <= 0.0 {
+            return false;
+        }
+
+        let center = self.pos + self.size * 0.5;
+        if overlap_x < overlap_y {
+            if ball_pos.x < center.x {
+                ball_pos.x -= overlap_x;
+                ball_vel.x = -ball_vel.x.abs();
+            } else {
+                ball_pos.x += overlap_x;
+                ball_vel.x = ball_vel.x.abs();
+            }
+        } else if ball_pos.y < center.y {
+            ball_pos.y -= overlap_y;
+            ball_vel.y = -ball_vel.y.abs();
+        } else {
+            ball_pos.y += overlap_y;
+            ball_vel.y = ball_vel.y.abs();
+        }
+        true
+    }
+}
+
+// a long thin bar pinned at its center that keeps turning, deflecting balls off whichever way
+// it is facing
+pub struct Spinner {
+    pub center: Vector2,
+    pub length: f32,
+    pub thickness: f32,
+    // in radians, and radians per second
+    pub angle: f32,
+    pub speed: f32,
+}
+
+impl Spinner {
+    pub fn update(&mut self, delta_time: f32) {
+        self.angle = (self.angle + self.speed * delta_time) % std::f32::consts::TAU;
+    }
+
+    // get both ends of the bar
+    pub fn ends(&self) -> (Vector2, Vector2) {
+        let half = Vector2::new(self.length / 2.0, 0.0).rotate(self.angle);
+        (self.center - half, self.center + half)
+    }
+
+    // bounce a ball of `ball_size` off the side of the bar it ran into, as seen from the bar
+    // moving under it, and move it back out so that it can't get stuck inside, returning whether
+    // it bounced
+    pub fn collide(&self, ball_pos: &mut Vector2, ball_vel: &mut Vector2, ball_size: f32) -> bool {
+        let (start, end) = self.ends();
+        let along = end - start;
+        let t = ((*ball_pos - start).dot(along) / along.dot(along)).clamp(0.0, 1.0);
+        let closest = start + along * t;
+
+        let reach = (ball_size + self.thickness) / 2.0;
+        let distance = (*ball_pos - closest).length();
+        if distance >= reach {
+            return false;
+        }
+        // a ball right on the bar gets pushed out to the side it is facing
+        let normal = if distance == 0.0 {
+            along.normalize().rotate(std::f32::consts::FRAC_PI_2)
+        } else {
+            (*ball_pos - closest).normalize()
+        };
+        *ball_pos = closest + normal * reach;
+
+        // the bar only moves as fast as the point that was hit, which is slow close to the pivot,
+        // so a ball there isn't flung away
+        let arm = closest - self.center;
+        let surface_vel = Vector2::new(-arm.y, arm.x) * self.speed;
+        let relative_vel = *ball_vel - surface_vel;
+        let approach = relative_vel.dot(normal);
+        if approach >= 0.0 {
+            return false;
+        }
+        *ball_vel = relative_vel - normal * (2.0 * approach) + surface_vel;
+        true
+    }
+}
+
+// arrangements of obstacles that come with the game
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    Empty,
+    // four small square blocks around the center
+    Blocks,
+    // two tall blocks on either side of the center
+    Pillars,
+    // a turning bar in the center
+    Spinner,
+    // a pair of portals on either side of the field
+    Portals,
+    // a gravity well in the center that bends the paths of the balls
+    Well,
+    // two bands across the field where the wind blows opposite ways
+    Wind,
+}
+
+impl Layout {
+    // place the obstacles in a field of the given size, keeping clear of the center and the
+    // edges where the balls are served and the paddles move
+    pub fn obstacles(self, width: f32, height: f32, thickness: f32) -> Vec<Obstacle> {
+        match self {
+            Layout::Empty | Layout::Spinner | Layout::Portals | Layout::Well | Layout::Wind => {
+                Vec::new()
+            }
+            Layout::Blocks => [(1.0, 1.0), (2.0, 1.0), (1.0, 2.0), (2.0, 2.0)]
+                .into_iter()
+                .map(|(x, y)| {
+                    Obstacle::new(
+                        width * x / 3.0,
+                        height * y / 3.0,
+                        thickness * 3.0,
+                        thickness * 3.0,
+                    )
+                })
+                .collect(),
+            Layout::Pillars => [1.0, 2.0]
+                .into_iter()
+                .map(|x| {
+                    Obstacle::new(width * x / 3.0, height / 2.0, thickness * 2.0, height / 4.0)
+                })
+                .collect(),
+        }
+    }
+
+    // get the turning bar in the center of the field if there is one, turning at `speed`
+    // radians per second
+    pub fn spinner(self, width: f32, height: f32, thickness: f32, speed: f32) -> Option<Spinner> {
+        match self {
+            Layout::Spinner => Some(Spinner {
+                center: Vector2::new(width / 2.0, height / 2.0),
+                length: width.min(height) / 4.0,
+                thickness: thickness / 2.0,
+                angle: 0.0,
+                speed,
+            }),
+            _ => None,
+        }
+    }
+
+    // get the pairs of portals in the field, facing each other so that a ball goes on across
+    // the field in much the same direction it came in from
+    pub fn portals(self, width: f32, height: f32, thickness: f32) -> Vec<PortalPair> {
+        match self {
+            Layout::Portals => vec![PortalPair {
+                portals: [
+                    Portal {
+                        center: Vector2::new(width / 5.0, height / 3.0),
+                        size: thickness * 4.0,
+                        facing: Vector2::new(1.0, 0.0),
+                    },
+                    Portal {
+                        center: Vector2::new(width * 4.0 / 5.0, height / 3.0),
+                        size: thickness * 4.0,
+                        facing: Vector2::new(-1.0, 0.0),
+                    },
+                ],
+                colors: [Color::RGB(250, 150, 40), Color::RGB(40, 110, 250)],
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    // get the gravity well in the center of the field if there is one, pulling a ball a third of
+    // the way across the field about as hard as the ball is fast every second
+    pub fn well(self, width: f32, height: f32, thickness: f32) -> Option<GravityWell> {
+        match self {
+            Layout::Well => {
+                let distance = width.min(height) / 3.0;
+                Some(GravityWell {
+                    center: Vector2::new(width / 2.0, height / 2.0),
+                    strength: 400.0 * distance * distance,
+                    radius: thickness * 1.5,
+                    time: 0.0,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    // get the zones where the wind blows, as bands across the middle half of the field, the
+    // upper one blowing right and the lower one left
+    pub fn winds(self, width: f32, height: f32, thickness: f32) -> Vec<WindZone> {
+        match self {
+            Layout::Wind => [(1.0, 1.0), (2.0, -1.0)]
+                .into_iter()
+                .map(|(y, direction)| WindZone {
+                    pos: Vector2::new(width / 4.0, height * y / 3.0 - thickness * 2.0),
+                    size: Vector2::new(width / 2.0, thickness * 4.0),
+                    force: Vector2::new(direction * 400.0, 0.0),
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "empty" => Ok(Layout::Empty),
+            "blocks" => Ok(Layout::Blocks),
+            "pillars" => Ok(Layout::Pillars),
+            "spinner" => Ok(Layout::Spinner),
+            "portals" => Ok(Layout::Portals),
+            "well" => Ok(Layout::Well),
+            "wind" => Ok(Layout::Wind),
+            _ => Err(format!("Unknown layout: {s}")),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use sdl2::rect::Rect;
+
+use crate::ball::Ball;
+use crate::config::GameConfig;
+use crate::vector::Vector2;
+
+// a paddle that moves back and forth along one side of the field
+pub struct Paddle {
+    // center along the side it moves on, and its top or left edge across it
+    pub pos: Vector2,
+    pub dir: i32,
+    // where along its side the paddle is being pointed at with the mouse, if it is
+    pub target: Option<f32>,
+    // long side of the paddle
+    pub width: f32,
+    pub thickness: f32,
+    // how fast the paddle is moving along its side, negative towards the top or the left
+    pub speed: f32,
+    // position along its side before the last move, so that a ball the paddle swept past
+    // during the move still counts as hit
+    pub last_along: f32,
+    // top speed, and how quickly the paddle speeds up while steered and slows down once let go
+    pub velocity: f32,
+    pub acceleration: f32,
+    pub friction: f32,
+    // whether it moves up and down on the left or right side rather than left and right
+    pub vertical: bool,
+}
+
+impl Paddle {
+    pub fn new(pos: Vector2, vertical: bool, config: &GameConfig) -> Paddle {
+        Paddle {
+            pos,
+            dir: 0,
+            target: None,
+            width: config.paddle_width,
+            thickness: config.thickness,
+            speed: 0.0,
+            last_along: if vertical { pos.y } else { pos.x },
+            velocity: config.paddle_velocity,
+            acceleration: config.paddle_acceleration,
+            friction: config.paddle_friction,
+            vertical,
+        }
+    }
+
+    // get the position along the side it moves on
+    pub fn along(&self) -> f32 {
+        if self.vertical {
+            self.pos.y
+        } else {
+            self.pos.x
+        }
+    }
+
+    pub fn vel(&self) -> Vector2 {
+        if self.vertical {
+            Vector2::new(0.0, self.speed)
+        } else {
+            Vector2::new(self.speed, 0.0)
+        }
+    }
+
+    // move the paddle the way it is being steered, or straight to where it is pointed at,
+    // keeping it between the walls at `min` and `max` along its side
+    pub fn update(&mut self, delta_time: f32, min: f32, max: f32) {
+        match self.target {
+            Some(target) => self.jump_to(target, delta_time, min, max),
+            None => {
+                // speed up towards top speed the way the paddle is steered, which takes a moment
+                // longer when turning around, or slow down to a stop once let go
+                let rate = if self.dir == 0 {
+                    self.friction
+                } else {
+                    self.acceleration
+                };
+                let step = rate * delta_time;
+                self.speed += (self.dir as f32 * self.velocity - self.speed).clamp(-step, step);
+                let along = self.along() + self.speed * delta_time;
+                self.move_to(along, min, max);
+            }
+        }
+    }
+
+    // move the paddle towards `target` along its side, no faster than its top speed
+    pub fn move_towards(&mut self, target: f32, delta_time: f32, min: f32, max: f32) {
+        let step = self.velocity * delta_time;
+        let along = self.along() + (target - self.along()).clamp(-step, step);
+        self.jump_to(along, delta_time, min, max);
+    }
+
+    // move the paddle straight to `along`, taking it to be moving as fast as it went there
+    fn jump_to(&mut self, along: f32, delta_time: f32, min: f32, max: f32) {
+        let from = self.along();
+        self.move_to(along, min, max);
+        if delta_time > 0.0 {
+            self.speed = ((self.along() - from) / delta_time).clamp(-self.velocity, self.velocity);
+        }
+    }
+
+    // move the paddle to `along`, stopping it dead against a wall so that it doesn't keep
+    // pushing into it
+    fn move_to(&mut self, along: f32, min: f32, max: f32) {
+        self.last_along = self.along();
+        let clamped = along.clamp(min + self.width / 2.0, max - self.width / 2.0);
+        if clamped != along {
+            self.speed = 0.0;
+        }
+        if self.vertical {
+            self.pos.y = clamped;
+        } else {
+            self.pos.x = clamped;
+        }
+    }
+
+    // whether the center of a ball is over the paddle, or was passed over by it in the last
+    // move, whichever way the ball is going
+    pub fn contains_ball(&self, ball: &Ball) -> bool {
+        let (ball_along, ball_across, across) = if self.vertical {
+            (ball.pos.y, ball.pos.x, self.pos.x)
+        } else {
+            (ball.pos.x, ball.pos.y, self.pos.y)
+        };
+        ball_along >= self.last_along.min(self.along()) - self.width / 2.0
+            && ball_along <= self.last_along.max(self.along()) + self.width / 2.0
+            && ball_across >= across
+            && ball_across <= across + self.thickness
+    }
+
+    pub fn rect(&self) -> Rect {
+        if self.vertical {
+            Rect::new(
+                self.pos.x as i32,
+                (self.pos.y - self.width / 2.0) as i32,
+                self.thickness as u32,
+                self.width as u32,
+            )
+        } else {
+            Rect::new(
+                (self.pos.x - self.width / 2.0) as i32,
+                self.pos.y as i32,
+                self.width as u32,
+                self.thickness as u32,
+            )
+        }
+    }
+}
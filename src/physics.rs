@@ -0,0 +1,601 @@
+use std::collections::VecDeque;
+
+use crate::ball::Ball;
+use crate::paddle::Paddle;
+use crate::vector::Vector2;
+
+pub const BALL_MAX_SPEED: f32 = 900.0;
+const MAX_BOUNCE_ANGLE: f32 = 75.0 * std::f32::consts::PI / 180.0;
+const MIN_BOUNCE_AWAY_RATIO: f32 = 0.35;
+// share of the width of the paddle in its middle that a ball has to hit for a perfect hit, and
+// how much faster such a ball comes off it
+const PERFECT_HIT_ZONE: f32 = 0.15;
+const PERFECT_HIT_SPEED_SCALE: f32 = 1.1;
+// how far inside a wall a ball is put back, so that it is never left sitting on the wall itself
+const WALL_MARGIN: f32 = 0.01;
+
+// the walls balls bounce off, with an infinite edge for a side that is open
+#[derive(Clone, Copy)]
+pub struct Bounds {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+// how balls come off the paddle
+#[derive(Clone, Copy)]
+pub struct Bounce {
+    pub serve_speed: f32,
+    pub speed_growth: f32,
+    pub max_speed_scale: f32,
+    // how much of the paddle's speed turns into spin
+    pub spin: f32,
+    // whether balls stick to the paddle instead of bouncing off it
+    pub sticky: bool,
+}
+
+// a paddle guarding a side of the field, with the direction pointing from that side into the
+// field, which balls are sent back along
+#[derive(Clone, Copy)]
+pub struct Guard<'a> {
+    pub paddle: &'a Paddle,
+    pub inward: Vector2,
+}
+
+// how many times balls hit something during a step
+#[derive(Default)]
+pub struct Contacts {
+    pub walls: u32,
+    pub paddle: u32,
+    // how many of the paddle hits were on the first paddle
+    pub first_paddle: u32,
+    // where along the top wall balls hit it
+    pub top: Vec<f32>,
+    // where balls were when they hit the middle of a paddle at the bottom
+    pub perfect: Vec<Vector2>,
+}
+
+// move the balls by `delta_time`, bouncing them off the walls and the paddles, which have
+// already been moved, and return what they hit, with the balls waiting to be served or sticking
+// to a paddle all belonging to the first paddle
+pub fn step(
+    balls: &mut VecDeque<Ball>,
+    paddles: &[Guard],
+    delta_time: f32,
+    bounds: Bounds,
+    bounce: Bounce,
+) -> Contacts {
+    let mut contacts = Contacts::default();
+
+    for ball in balls {
+        if let Some(first) = paddles.first() {
+            if carry_attached(ball, first.paddle) {
+                continue;
+            }
+        }
+
+        ball.pos += ball.vel * delta_time;
+
+        // collision detection with walls
+        if collide_walls(&mut ball.pos.x, &mut ball.vel.x, bounds.left, bounds.right) {
+            ball.damp_spin();
+            contacts.walls += 1;
+        }
+        if collide_walls(&mut ball.pos.y, &mut ball.vel.y, bounds.top, bounds.bottom) {
+            ball.damp_spin();
+            contacts.walls += 1;
+            // a ball that came off the top wall is heading down now
+            if ball.vel.y > 0.0 {
+                contacts.top.push(ball.pos.x);
+            }
+        }
+
+        // collision detection with paddles, which only send back balls heading out past them
+        for (i, &Guard { paddle, inward }) in paddles.iter().enumerate() {
+            if !paddle.contains_ball(ball) || ball.vel.dot(inward) >= 0.0 {
+                continue;
+            }
+            if bounce.sticky && i == 0 {
+                stick_to_paddle(ball, paddle);
+            } else {
+                let ball_along = if paddle.vertical {
+                    ball.pos.y
+                } else {
+                    ball.pos.x
+                };
+                let offset = hit_offset(ball_along, paddle.along(), paddle.width);
+                let mut speed = ball.hit_speed(
+                    bounce.serve_speed,
+                    bounce.speed_growth,
+                    bounce.max_speed_scale,
+                );
+                // only the paddles at the bottom are rewarded for perfect hits
+                if inward.y < 0.0 && is_perfect_hit(offset) {
+                    speed *= PERFECT_HIT_SPEED_SCALE;
+                    contacts.perfect.push(ball.pos);
+                }
+                let (along, away) = bounce_velocity(speed.min(BALL_MAX_SPEED), offset);
+                ball.vel = if paddle.vertical {
+                    Vector2::new(inward.x * away, along)
+                } else {
+                    Vector2::new(along, inward.y * away)
+                };
+                ball.take_spin(paddle.vel(), bounce.spin);
+            }
+            contacts.paddle += 1;
+            if i == 0 {
+                contacts.first_paddle += 1;
+            }
+        }
+    }
+    contacts
+}
+
+// get the path a ball would take over the next `duration` seconds, one point for each step of
+// `delta_time`, curving with its spin and bouncing off the walls the same way as in `step`, but
+// going straight past the paddles and everything else in the field
+pub fn predict_path(ball: &Ball, bounds: Bounds, duration: f32, delta_time: f32) -> Vec<Vector2> {
+    let mut ball = Ball {
+        trail: VecDeque::new(),
+        ..*ball
+    };
+    let steps = (duration / delta_time) as usize;
+    let mut path = Vec::with_capacity(steps + 1);
+    path.push(ball.pos);
+    for _ in 0..steps {
+        ball.curve(delta_time);
+        ball.pos += ball.vel * delta_time;
+        if collide_walls(&mut ball.pos.x, &mut ball.vel.x, bounds.left, bounds.right) {
+            ball.damp_spin();
+        }
+        if collide_walls(&mut ball.pos.y, &mut ball.vel.y, bounds.top, bounds.bottom) {
+            ball.damp_spin();
+        }
+        path.push(ball.pos);
+    }
+    path
+}
+
+// bounce balls of `ball_size` that run into each other off one another, as an elastic collision
+// exchanges their velocity components along the line between their centers, the lighter ball
+// taking more of the change
+pub fn collide_balls(balls: &mut VecDeque<Ball>, ball_size: f32) {
+    for i in 0..balls.len() {
+        for j in (i + 1)..balls.len() {
+            let (a, b) = (&balls[i], &balls[j]);
+            if a.attached.is_some() || b.attached.is_some() {
+                continue;
+            }
+
+            let distance = (b.pos - a.pos).length();
+            if distance >= ball_size || distance == 0.0 {
+                continue;
+            }
+
+            // push the pair apart until they just touch, so that they don't keep colliding
+            // while they overlap
+            let normal = (b.pos - a.pos).normalize();
+            let closing_speed = (a.vel - b.vel).dot(normal);
+            let (mass_a, mass_b) = (a.kind.mass(), b.kind.mass());
+            let separation = normal * ((ball_size - distance) / 2.0);
+            balls[i].pos -= separation;
+            balls[j].pos += separation;
+
+            // balls that are already moving apart have bounced off each other before
+            if closing_speed > 0.0 {
+                let total = mass_a + mass_b;
+                balls[i].vel -= normal * (closing_speed * 2.0 * mass_b / total);
+                balls[j].vel += normal * (closing_speed * 2.0 * mass_a / total);
+            }
+        }
+    }
+}
+
+// get where a ball hit a paddle, from -1.0 at one end through 0.0 at the center to 1.0 at
+// the other end
+fn hit_offset(ball_pos: f32, paddle_pos: f32, paddle_width: f32) -> f32 {
+    ((ball_pos - paddle_pos) / (paddle_width / 2.0)).clamp(-1.0, 1.0)
+}
+
+// whether a ball that hit a paddle at `offset` hit it right in the middle
+fn is_perfect_hit(offset: f32) -> bool {
+    // the offset runs over half the width of the paddle either way
+    offset.abs() <= PERFECT_HIT_ZONE
+}
+
+// split the speed of a ball that hit a paddle at `offset` into components along the paddle
+// and away from it, keeping enough of the latter so that the ball can't end up travelling
+// almost parallel to the paddle and never come back
+pub fn bounce_velocity(speed: f32, offset: f32) -> (f32, f32) {
+    let angle = offset.clamp(-1.0, 1.0) * MAX_BOUNCE_ANGLE;
+    let away = (speed * angle.cos()).max(speed * MIN_BOUNCE_AWAY_RATIO);
+    let along = (speed * speed - away * away)
+        .max(0.0)
+        .sqrt()
+        .copysign(angle);
+    (along, away)
+}
+
+// hold a ball that hit the sticky paddle where it landed until it is released
+pub fn stick_to_paddle(ball: &mut Ball, paddle: &Paddle) {
+    ball.attached = Some(ball.pos.x - paddle.pos.x);
+    ball.stuck = true;
+    ball.vel = Vector2::default();
+}
+
+// keep a ball that is waiting to be served on top of the paddle, returning whether it is
+pub fn carry_attached(ball: &mut Ball, paddle: &Paddle) -> bool {
+    match ball.attached {
+        Some(offset) => {
+            ball.pos.x = paddle.pos.x + offset;
+            ball.pos.y = paddle.pos.y - paddle.thickness / 2.0;
+            true
+        }
+        None => false,
+    }
+}
+
+// reflect a ball off walls that keep its position along one axis between `min` and `max`,
+// putting it back just inside the wall if it reached it or moved so fast that it went past it
+// within a single step, and return whether it hit one
+pub fn collide_walls(pos: &mut f32, vel: &mut f32, min: f32, max: f32) -> bool {
+    let heading_out = if *pos <= min {
+        *pos = min + WALL_MARGIN;
+        *vel < 0.0
+    } else if *pos >= max {
+        *pos = max - WALL_MARGIN;
+        *vel > 0.0
+    } else {
+        return false;
+    };
+    if heading_out {
+        *vel = -*vel;
+    }
+    heading_out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ball::BallKind;
+    use crate::config::GameConfig;
+
+    const BOUNDS: Bounds = Bounds {
+        left: 15.0,
+        right: 785.0,
+        top: 15.0,
+        bottom: f32::INFINITY,
+    };
+    const BOUNCE: Bounce = Bounce {
+        serve_speed: 400.0,
+        speed_growth: 1.0,
+        max_speed_scale: 1.0,
+        spin: 0.0,
+        sticky: false,
+    };
+    const STEP: f32 = 1.0 / 120.0;
+
+    // a paddle with its center along its side at `along`, and its top or left edge at `across`
+    fn paddle(along: f32, across: f32, vertical: bool) -> Paddle {
+        let pos = if vertical {
+            Vector2::new(across, along)
+        } else {
+            Vector2::new(along, across)
+        };
+        Paddle::new(pos, vertical, &GameConfig::default())
+    }
+
+    #[test]
+    fn ball_reflects_off_left_wall() {
+        let mut balls = VecDeque::from([Ball::at(
+            Vector2::new(16.0, 300.0),
+            Vector2::new(-300.0, 100.0),
+        )]);
+        let contacts = step(&mut balls, &[], STEP, BOUNDS, BOUNCE);
+        assert_eq!(contacts.walls, 1);
+        assert_eq!(balls[0].pos.x, BOUNDS.left + WALL_MARGIN);
+        assert_eq!(balls[0].vel, Vector2::new(300.0, 100.0));
+    }
+
+    #[test]
+    fn ball_reflects_off_top_wall() {
+        let mut balls = VecDeque::from([Ball::at(
+            Vector2::new(400.0, 16.0),
+            Vector2::new(100.0, -300.0),
+        )]);
+        let contacts = step(&mut balls, &[], STEP, BOUNDS, BOUNCE);
+        assert_eq!(contacts.walls, 1);
+        assert_eq!(contacts.top, vec![balls[0].pos.x]);
+        assert_eq!(balls[0].pos.y, BOUNDS.top + WALL_MARGIN);
+        assert_eq!(balls[0].vel, Vector2::new(100.0, 300.0));
+    }
+
+    #[test]
+    fn ball_reflects_off_paddle() {
+        let bottom = paddle(400.0, 585.0, false);
+        let guards = [Guard {
+            paddle: &bottom,
+            inward: Vector2::new(0.0, -1.0),
+        }];
+        let mut balls = VecDeque::from([Ball::at(
+            Vector2::new(420.0, 584.0),
+            Vector2::new(0.0, 300.0),
+        )]);
+        let contacts = step(&mut balls, &guards, STEP, BOUNDS, BOUNCE);
+        assert_eq!(contacts.paddle, 1);
+        assert_eq!(contacts.first_paddle, 1);
+        let vel = balls[0].vel;
+        assert!(vel.y < 0.0, "ball still heading down at {vel:?}");
+        assert!(
+            vel.x > 0.0,
+            "ball hit right of the center but went left at {vel:?}"
+        );
+        assert!((vel.length() - BOUNCE.serve_speed).abs() < 0.01);
+    }
+
+    #[test]
+    fn ball_reflects_off_side_paddle() {
+        let left = paddle(300.0, 0.0, true);
+        let right = paddle(300.0, 785.0, true);
+        let guards = [
+            Guard {
+                paddle: &left,
+                inward: Vector2::new(1.0, 0.0),
+            },
+            Guard {
+                paddle: &right,
+                inward: Vector2::new(-1.0, 0.0),
+            },
+        ];
+        let bounds = Bounds {
+            left: f32::NEG_INFINITY,
+            right: f32::INFINITY,
+            ..BOUNDS
+        };
+        let mut balls = VecDeque::from([Ball::at(
+            Vector2::new(788.0, 300.0),
+            Vector2::new(300.0, 0.0),
+        )]);
+        let contacts = step(&mut balls, &guards, STEP, bounds, BOUNCE);
+        assert_eq!(contacts.paddle, 1);
+        assert_eq!(contacts.first_paddle, 0);
+        assert!(balls[0].vel.x < 0.0);
+    }
+
+    #[test]
+    fn perfect_hit_on_bottom_paddle_is_boosted() {
+        let bottom = paddle(400.0, 585.0, false);
+        let guards = [Guard {
+            paddle: &bottom,
+            inward: Vector2::new(0.0, -1.0),
+        }];
+        let mut balls = VecDeque::from([Ball::at(
+            Vector2::new(400.0, 584.0),
+            Vector2::new(0.0, 300.0),
+        )]);
+        let contacts = step(&mut balls, &guards, STEP, BOUNDS, BOUNCE);
+        assert_eq!(contacts.perfect.len(), 1);
+        let boosted = BOUNCE.serve_speed * PERFECT_HIT_SPEED_SCALE;
+        assert!((balls[0].vel.length() - boosted).abs() < 0.01);
+    }
+
+    #[test]
+    fn perfect_hit_on_top_or_side_paddle_is_not_boosted() {
+        let top = paddle(400.0, 0.0, false);
+        let right = paddle(300.0, 785.0, true);
+        let bounds = Bounds {
+            left: f32::NEG_INFINITY,
+            right: f32::INFINITY,
+            top: f32::NEG_INFINITY,
+            bottom: f32::INFINITY,
+        };
+        let cases = [
+            (
+                &top,
+                Vector2::new(0.0, 1.0),
+                Vector2::new(400.0, 10.0),
+                Vector2::new(0.0, -300.0),
+            ),
+            (
+                &right,
+                Vector2::new(-1.0, 0.0),
+                Vector2::new(790.0, 300.0),
+                Vector2::new(300.0, 0.0),
+            ),
+        ];
+        for (paddle, inward, pos, vel) in cases {
+            let guards = [Guard { paddle, inward }];
+            let mut balls = VecDeque::from([Ball::at(pos, vel)]);
+            let contacts = step(&mut balls, &guards, STEP, bounds, BOUNCE);
+            assert_eq!(contacts.paddle, 1);
+            assert!(contacts.perfect.is_empty());
+            assert!((balls[0].vel.length() - BOUNCE.serve_speed).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn ball_heading_into_field_passes_paddle() {
+        let bottom = paddle(400.0, 585.0, false);
+        let guards = [Guard {
+            paddle: &bottom,
+            inward: Vector2::new(0.0, -1.0),
+        }];
+        let mut balls = VecDeque::from([Ball::at(
+            Vector2::new(420.0, 590.0),
+            Vector2::new(0.0, -300.0),
+        )]);
+        let contacts = step(&mut balls, &guards, STEP, BOUNDS, BOUNCE);
+        assert_eq!(contacts.paddle, 0);
+        assert_eq!(balls[0].vel, Vector2::new(0.0, -300.0));
+    }
+
+    #[test]
+    fn hit_offset_runs_from_end_to_end() {
+        assert_eq!(hit_offset(100.0, 100.0, 80.0), 0.0);
+        assert_eq!(hit_offset(60.0, 100.0, 80.0), -1.0);
+        assert_eq!(hit_offset(120.0, 100.0, 80.0), 0.5);
+        // a ball caught just past an end still counts as hitting it
+        assert_eq!(hit_offset(150.0, 100.0, 80.0), 1.0);
+    }
+
+    #[test]
+    fn bounce_keeps_speed() {
+        for offset in [-1.0, -0.6, -0.2, 0.0, 0.3, 0.8, 1.0] {
+            let (along, away) = bounce_velocity(400.0, offset);
+            let speed = (along * along + away * away).sqrt();
+            assert!(
+                (speed - 400.0).abs() < 0.01,
+                "speed {speed} at offset {offset}"
+            );
+        }
+    }
+
+    #[test]
+    fn bounce_off_an_end_keeps_going_away() {
+        for offset in [-1.0, 1.0] {
+            let (_, away) = bounce_velocity(400.0, offset);
+            assert!(away >= 400.0 * MIN_BOUNCE_AWAY_RATIO - 0.01);
+        }
+    }
+
+    #[test]
+    fn bounce_goes_the_side_it_hit() {
+        assert!(bounce_velocity(400.0, -0.5).0 < 0.0);
+        assert!(bounce_velocity(400.0, 0.5).0 > 0.0);
+        assert_eq!(bounce_velocity(400.0, 0.0), (0.0, 400.0));
+    }
+
+    #[test]
+    fn fast_ball_is_kept_inside_walls() {
+        // fast enough to cross the whole field several times over within a single long step
+        let bounds = Bounds {
+            bottom: 585.0,
+            ..BOUNDS
+        };
+        let mut balls = VecDeque::from([Ball::at(
+            Vector2::new(400.0, 300.0),
+            Vector2::new(20000.0, -15000.0),
+        )]);
+        for _ in 0..10 {
+            let vel = balls[0].vel;
+            step(&mut balls, &[], 0.1, bounds, BOUNCE);
+            let ball = &balls[0];
+            assert!(ball.pos.x > bounds.left && ball.pos.x < bounds.right);
+            assert!(ball.pos.y > bounds.top && ball.pos.y < bounds.bottom);
+            // every step takes it past a wall on both axes, so it turns around on both
+            assert_eq!(ball.vel, vel * -1.0);
+        }
+    }
+
+    #[test]
+    fn ball_on_wall_is_reflected_once() {
+        let mut balls = VecDeque::from([Ball::at(
+            Vector2::new(BOUNDS.left, 300.0),
+            Vector2::new(-300.0, 100.0),
+        )]);
+        let mut walls = 0;
+        for _ in 0..10 {
+            walls += step(&mut balls, &[], STEP, BOUNDS, BOUNCE).walls;
+            assert!(balls[0].pos.x > BOUNDS.left);
+        }
+        assert_eq!(walls, 1);
+        assert_eq!(balls[0].vel, Vector2::new(300.0, 100.0));
+    }
+
+    #[test]
+    fn ball_along_wall_is_moved_off_it() {
+        let mut balls = VecDeque::from([
+            Ball::at(Vector2::new(BOUNDS.left, 300.0), Vector2::new(0.0, 300.0)),
+            Ball::at(Vector2::new(BOUNDS.right, 300.0), Vector2::new(0.0, 300.0)),
+        ]);
+        let contacts = step(&mut balls, &[], STEP, BOUNDS, BOUNCE);
+        assert_eq!(contacts.walls, 0);
+        assert!(balls[0].pos.x > BOUNDS.left);
+        assert!(balls[1].pos.x < BOUNDS.right);
+        assert_eq!(balls[0].vel, Vector2::new(0.0, 300.0));
+    }
+
+    fn momentum(balls: &VecDeque<Ball>) -> Vector2 {
+        balls.iter().fold(Vector2::default(), |sum, ball| {
+            sum + ball.vel * ball.kind.mass()
+        })
+    }
+
+    #[test]
+    fn head_on_balls_swap_velocities() {
+        let mut balls = VecDeque::from([
+            Ball::at(Vector2::new(100.0, 100.0), Vector2::new(200.0, 0.0)),
+            Ball::at(Vector2::new(110.0, 100.0), Vector2::new(-200.0, 0.0)),
+        ]);
+        collide_balls(&mut balls, 15.0);
+        assert_eq!(balls[0].vel, Vector2::new(-200.0, 0.0));
+        assert_eq!(balls[1].vel, Vector2::new(200.0, 0.0));
+        // pushed apart until they just touch
+        assert_eq!((balls[1].pos - balls[0].pos).length(), 15.0);
+    }
+
+    #[test]
+    fn glancing_balls_only_exchange_velocity_along_the_line_between_them() {
+        let mut balls = VecDeque::from([
+            Ball::at(Vector2::new(100.0, 100.0), Vector2::new(200.0, 0.0)),
+            Ball::at(Vector2::new(110.0, 110.0), Vector2::new(0.0, 0.0)),
+        ]);
+        collide_balls(&mut balls, 15.0);
+        let normal = Vector2::new(1.0, 1.0).normalize();
+        let side = Vector2::new(-1.0, 1.0).normalize();
+        // the resting ball is pushed off along the line, the other keeps its speed across it
+        assert!((balls[1].vel.dot(side)).abs() < 0.01);
+        assert!(balls[1].vel.dot(normal) > 0.0);
+        assert!((balls[0].vel.dot(side) - Vector2::new(200.0, 0.0).dot(side)).abs() < 0.01);
+        assert!((balls[0].vel.dot(normal)).abs() < 0.01);
+    }
+
+    #[test]
+    fn separating_balls_keep_their_velocities() {
+        let mut balls = VecDeque::from([
+            Ball::at(Vector2::new(100.0, 100.0), Vector2::new(-200.0, 0.0)),
+            Ball::at(Vector2::new(110.0, 100.0), Vector2::new(200.0, 0.0)),
+        ]);
+        collide_balls(&mut balls, 15.0);
+        assert_eq!(balls[0].vel, Vector2::new(-200.0, 0.0));
+        assert_eq!(balls[1].vel, Vector2::new(200.0, 0.0));
+    }
+
+    #[test]
+    fn balls_moving_together_keep_their_velocities() {
+        let vel = Vector2::new(150.0, -80.0);
+        let mut balls = VecDeque::from([
+            Ball::at(Vector2::new(100.0, 100.0), vel),
+            Ball::at(Vector2::new(106.0, 103.0), vel),
+        ]);
+        collide_balls(&mut balls, 15.0);
+        assert_eq!(balls[0].vel, vel);
+        assert_eq!(balls[1].vel, vel);
+        // pushed apart so that they don't collide again on the next step
+        assert!((balls[1].pos - balls[0].pos).length() >= 15.0 - 0.001);
+        let (a, b) = (balls[0].pos, balls[1].pos);
+        collide_balls(&mut balls, 15.0);
+        assert_eq!((balls[0].pos, balls[1].pos), (a, b));
+    }
+
+    #[test]
+    fn collision_of_unequal_masses_keeps_momentum() {
+        let mut heavy = Ball::at(Vector2::new(100.0, 100.0), Vector2::new(150.0, 30.0));
+        heavy.kind = BallKind::Heavy;
+        let mut light = Ball::at(Vector2::new(108.0, 104.0), Vector2::new(-250.0, 10.0));
+        light.kind = BallKind::Light;
+        let mut balls = VecDeque::from([heavy, light]);
+        let before = momentum(&balls);
+        collide_balls(&mut balls, 15.0);
+        let after = momentum(&balls);
+        assert!(
+            (after - before).length() < 0.01,
+            "{before:?} became {after:?}"
+        );
+        // the lighter ball takes more of the change
+        let heavy_change = (balls[0].vel - Vector2::new(150.0, 30.0)).length();
+        let light_change = (balls[1].vel - Vector2::new(-250.0, 10.0)).length();
+        assert!(light_change > heavy_change);
+    }
+}
@@ -0,0 +1,40 @@
+use sdl2::pixels::Color;
+
+use crate::vector::Vector2;
+
+// a square zone that sends balls entering it out of the other portal of its pair
+pub struct Portal {
+    pub center: Vector2,
+    pub size: f32,
+    // direction balls come out of it in, as a vector of length 1.0
+    pub facing: Vector2,
+}
+
+impl Portal {
+    pub fn contains(&self, pos: Vector2) -> bool {
+        (pos.x - self.center.x).abs() <= self.size / 2.0
+            && (pos.y - self.center.y).abs() <= self.size / 2.0
+    }
+}
+
+pub struct PortalPair {
+    pub portals: [Portal; 2],
+    pub colors: [Color; 2],
+}
+
+impl PortalPair {
+    // move a ball that is inside either of the portals out of the other one, turning it by as
+    // much as the exit faces away from the way the entrance faces in, and return whether it did
+    pub fn teleport(&self, pos: &mut Vector2, vel: &mut Vector2, ball_size: f32) -> bool {
+        let Some(entrance) = self.portals.iter().position(|portal| portal.contains(*pos)) else {
+            return false;
+        };
+        let (from, to) = (&self.portals[entrance], &self.portals[1 - entrance]);
+
+        let turn = to.facing.angle() - (from.facing * -1.0).angle();
+        *vel = vel.rotate(turn);
+        // come out just clear of the exit so that the ball isn't inside it any more
+        *pos = to.center + to.facing * ((to.size + ball_size) / 2.0);
+        true
+    }
+}
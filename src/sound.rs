@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use sdl2::mixer::{self, Channel, Chunk};
+use sdl2::{AudioSubsystem, Sdl};
+
+#[derive(Clone, Copy)]
+pub enum Sound {
+    Wall,
+    Paddle,
+}
+
+// the sound effects, kept together with the audio subsystem that has to stay open to play them
+pub struct Sounds {
+    _audio_subsystem: AudioSubsystem,
+    wall: Chunk,
+    paddle: Chunk,
+}
+
+impl Sounds {
+    pub fn load(sdl_context: &Sdl, wall_path: &Path, paddle_path: &Path) -> Result<Sounds, String> {
+        let audio_subsystem = sdl_context
+            .audio()
+            .map_err(|err| format!("Failed to initialize SDL2 audio subsystem: {err}"))?;
+        mixer::open_audio(mixer::DEFAULT_FREQUENCY, mixer::DEFAULT_FORMAT, 1, 512)
+            .map_err(|err| format!("Failed to open audio device: {err}"))?;
+
+        let load = |path: &Path| {
+            Chunk::from_file(path)
+                .map_err(|err| format!("Failed to load sound {}: {err}", path.display()))
+        };
+        Ok(Sounds {
+            _audio_subsystem: audio_subsystem,
+            wall: load(wall_path)?,
+            paddle: load(paddle_path)?,
+        })
+    }
+
+    pub fn play(&self, sound: Sound) {
+        let chunk = match sound {
+            Sound::Wall => &self.wall,
+            Sound::Paddle => &self.paddle,
+        };
+        // with every channel busy the sound is just skipped, which isn't worth reporting
+        let _ = Channel::all().play(chunk, 0);
+    }
+}
@@ -0,0 +1,54 @@
+// a segment of the top wall that gives bonus points when a ball hits it, after which it goes
+// away for a moment and comes back somewhere else along the wall
+pub struct Target {
+    // left end of the segment
+    pub x: f32,
+    pub active: bool,
+    // seconds until the target comes back after it was hit
+    pub respawn_timer: f32,
+    // seconds left of the flash where it was hit
+    pub flash: f32,
+}
+
+impl Target {
+    pub const WIDTH: f32 = 60.0;
+    pub const FLASH_TIME: f32 = 0.3;
+    const RESPAWN_TIME: f32 = 2.0;
+
+    pub fn new(x: f32) -> Target {
+        Target {
+            x,
+            active: true,
+            respawn_timer: 0.0,
+            flash: 0.0,
+        }
+    }
+
+    // whether a ball hitting the wall at `x` hits the target
+    pub fn contains(&self, x: f32) -> bool {
+        self.active && x >= self.x && x <= self.x + Self::WIDTH
+    }
+
+    // whether the segment from `x` along the wall would overlap the target, counting it while
+    // it is waiting to come back so that nothing moves into its place in the meantime
+    pub fn overlaps(&self, x: f32) -> bool {
+        x < self.x + Self::WIDTH && self.x < x + Self::WIDTH
+    }
+
+    pub fn hit(&mut self) {
+        self.active = false;
+        self.respawn_timer = Self::RESPAWN_TIME;
+        self.flash = Self::FLASH_TIME;
+    }
+
+    // count down the flash and the wait after a hit, and get whether the target is ready to be
+    // put back on the wall
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        self.flash = (self.flash - delta_time).max(0.0);
+        if self.active {
+            return false;
+        }
+        self.respawn_timer = (self.respawn_timer - delta_time).max(0.0);
+        self.respawn_timer == 0.0
+    }
+}
@@ -0,0 +1,94 @@
+use std::str::FromStr;
+
+use sdl2::pixels::Color;
+
+// colors the game is drawn in
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub background: Color,
+    // walls, paddles and text
+    pub foreground: Color,
+    // colors given to the balls of the normal kind in turn, all standing out against the
+    // background, as the other kinds keep their own colors
+    pub balls: [Color; 6],
+}
+
+impl Theme {
+    // white on a sky blue background
+    pub const BLUE: Theme = Theme {
+        background: Color::RGB(124, 199, 232),
+        foreground: Color::RGB(255, 255, 255),
+        balls: [
+            Color::RGB(255, 255, 255),
+            Color::RGB(220, 40, 60),
+            Color::RGB(120, 40, 180),
+            Color::RGB(20, 120, 60),
+            Color::RGB(240, 120, 0),
+            Color::RGB(20, 40, 120),
+        ],
+    };
+    // white on black, like the arcade original
+    pub const CLASSIC: Theme = Theme {
+        background: Color::RGB(0, 0, 0),
+        foreground: Color::RGB(255, 255, 255),
+        balls: [
+            Color::RGB(255, 255, 255),
+            Color::RGB(255, 80, 80),
+            Color::RGB(255, 220, 60),
+            Color::RGB(90, 230, 90),
+            Color::RGB(80, 220, 240),
+            Color::RGB(240, 100, 240),
+        ],
+    };
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "blue" | "current" => Ok(Theme::BLUE),
+            "classic" => Ok(Theme::CLASSIC),
+            _ => Err(format!("Unknown theme: {s}")),
+        }
+    }
+}
+
+// get the color a ball going at `speed` is drawn in when the balls are colored by their speed,
+// going from white at `min` through yellow halfway to red at `max` and beyond
+pub fn speed_color(speed: f32, min: f32, max: f32) -> Color {
+    let share = if max > min {
+        ((speed - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    if share < 0.5 {
+        Color::RGB(255, 255, (255.0 * (1.0 - share * 2.0)) as u8)
+    } else {
+        Color::RGB(255, (255.0 * (2.0 - share * 2.0)) as u8, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speed_color_endpoints() {
+        assert_eq!(speed_color(200.0, 200.0, 600.0), Color::RGB(255, 255, 255));
+        assert_eq!(speed_color(600.0, 200.0, 600.0), Color::RGB(255, 0, 0));
+    }
+
+    #[test]
+    fn speed_color_midpoint() {
+        assert_eq!(speed_color(400.0, 200.0, 600.0), Color::RGB(255, 255, 0));
+    }
+
+    #[test]
+    fn speed_color_clamps() {
+        assert_eq!(speed_color(0.0, 200.0, 600.0), Color::RGB(255, 255, 255));
+        assert_eq!(speed_color(5000.0, 200.0, 600.0), Color::RGB(255, 0, 0));
+        // an empty range has nothing to go by
+        assert_eq!(speed_color(400.0, 400.0, 400.0), Color::RGB(255, 255, 255));
+    }
+}
@@ -0,0 +1,84 @@
+// a computer player of the tournament, steering the right paddle
+#[derive(Clone, Copy)]
+pub struct Opponent {
+    pub name: &'static str,
+    // seconds between the opponent aiming at the ball again
+    pub reaction_time: f32,
+    // share of the configured top speed of the paddles it moves at
+    pub speed_scale: f32,
+    // how far off the ball it may aim, in pixels
+    pub error_margin: f32,
+}
+
+// a run through a ladder of opponents, each faster and more accurate than the one before, where
+// winning a match moves on to the next one and losing ends the run
+pub struct Tournament {
+    roster: Vec<Opponent>,
+    // index of the opponent being played, or of the next one between matches
+    pub round: usize,
+    // seconds until the opponent aims again, and where along its side it is aiming
+    pub aim_timer: f32,
+    pub aim: f32,
+}
+
+impl Tournament {
+    const ROSTER: [Opponent; 5] = [
+        Opponent {
+            name: "Rookie",
+            reaction_time: 0.4,
+            speed_scale: 0.5,
+            error_margin: 60.0,
+        },
+        Opponent {
+            name: "Amateur",
+            reaction_time: 0.3,
+            speed_scale: 0.65,
+            error_margin: 45.0,
+        },
+        Opponent {
+            name: "Pro",
+            reaction_time: 0.2,
+            speed_scale: 0.8,
+            error_margin: 30.0,
+        },
+        Opponent {
+            name: "Veteran",
+            reaction_time: 0.15,
+            speed_scale: 0.9,
+            error_margin: 20.0,
+        },
+        Opponent {
+            name: "Champion",
+            reaction_time: 0.08,
+            speed_scale: 1.0,
+            error_margin: 10.0,
+        },
+    ];
+
+    // start from the first opponent, aiming at `aim` until it first reacts
+    pub fn start(aim: f32) -> Tournament {
+        Tournament {
+            roster: Self::ROSTER.to_vec(),
+            round: 0,
+            aim_timer: 0.0,
+            aim,
+        }
+    }
+
+    pub fn roster(&self) -> &[Opponent] {
+        &self.roster
+    }
+
+    pub fn opponent(&self) -> Opponent {
+        self.roster[self.round]
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.round + 1 == self.roster.len()
+    }
+
+    pub fn advance(&mut self) {
+        self.round += 1;
+        self.aim_timer = 0.0;
+    }
+}
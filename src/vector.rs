@@ -0,0 +1,131 @@
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vector2 {
+    pub fn new(x: f32, y: f32) -> Vector2 {
+        Vector2 { x, y }
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn dot(self, other: Vector2) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    // get the vector of length 1.0 pointing the same way, or a zero vector if there is no way
+    // it points
+    pub fn normalize(self) -> Vector2 {
+        let length = self.length();
+        if length == 0.0 {
+            Vector2::default()
+        } else {
+            self * (1.0 / length)
+        }
+    }
+
+    // get the angle from the x axis in radians
+    pub fn angle(self) -> f32 {
+        self.y.atan2(self.x)
+    }
+
+    // get the vector turned counterclockwise by `angle` radians
+    pub fn rotate(self, angle: f32) -> Vector2 {
+        let (sin, cos) = angle.sin_cos();
+        Vector2 {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+}
+
+impl Add for Vector2 {
+    type Output = Vector2;
+
+    fn add(self, other: Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl Sub for Vector2 {
+    type Output = Vector2;
+
+    fn sub(self, other: Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl Mul<f32> for Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, scale: f32) -> Vector2 {
+        Vector2 {
+            x: self.x * scale,
+            y: self.y * scale,
+        }
+    }
+}
+
+impl AddAssign for Vector2 {
+    fn add_assign(&mut self, other: Vector2) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Vector2 {
+    fn sub_assign(&mut self, other: Vector2) {
+        *self = *self - other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length() {
+        assert_eq!(Vector2::new(3.0, 4.0).length(), 5.0);
+        assert_eq!(Vector2::default().length(), 0.0);
+    }
+
+    #[test]
+    fn normalize() {
+        let unit = Vector2::new(3.0, -4.0).normalize();
+        assert!((unit.length() - 1.0).abs() < 1e-6);
+        assert_eq!(unit, Vector2::new(0.6, -0.8));
+    }
+
+    #[test]
+    fn normalize_zero_vector() {
+        let unit = Vector2::default().normalize();
+        assert!(!unit.x.is_nan() && !unit.y.is_nan());
+        assert_eq!(unit, Vector2::default());
+    }
+
+    #[test]
+    fn operators() {
+        let a = Vector2::new(1.0, 2.0);
+        let b = Vector2::new(3.0, -5.0);
+        assert_eq!(a + b, Vector2::new(4.0, -3.0));
+        assert_eq!(a - b, Vector2::new(-2.0, 7.0));
+        assert_eq!(b * 2.0, Vector2::new(6.0, -10.0));
+
+        let mut c = a;
+        c += b;
+        assert_eq!(c, a + b);
+        c -= b;
+        assert_eq!(c, a);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::vector::Vector2;
+
+// a point in the field that pulls balls towards it, harder the closer they get
+pub struct GravityWell {
+    pub center: Vector2,
+    // acceleration of a ball at a distance of 1.0, falling off with the square of the distance
+    pub strength: f32,
+    // size of the well as drawn, inside which the pull stops growing
+    pub radius: f32,
+    // seconds since the well appeared, to pulse with
+    pub time: f32,
+}
+
+impl GravityWell {
+    const MAX_ACCELERATION: f32 = 1500.0;
+    const PULSE_SPEED: f32 = 4.0;
+    const PULSE_SCALE: f32 = 0.2;
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+
+    // speed a ball up towards the well for `delta_time`, capping the pull so that a ball passing
+    // right by the center curves around it rather than being flung off at any speed
+    pub fn pull(&self, pos: Vector2, vel: &mut Vector2, delta_time: f32) {
+        let offset = self.center - pos;
+        let distance = offset.length().max(self.radius);
+        let acceleration = (self.strength / (distance * distance)).min(Self::MAX_ACCELERATION);
+        *vel += offset.normalize() * (acceleration * delta_time);
+    }
+
+    // get the radius to draw the well at, swelling and shrinking over time
+    pub fn pulse_radius(&self) -> f32 {
+        self.radius * (1.0 + Self::PULSE_SCALE * (self.time * Self::PULSE_SPEED).sin())
+    }
+}
@@ -0,0 +1,26 @@
+use crate::vector::Vector2;
+
+// a rectangular zone where the wind pushes balls the same way all the time
+pub struct WindZone {
+    // top left corner
+    pub pos: Vector2,
+    pub size: Vector2,
+    // acceleration of a ball inside the zone
+    pub force: Vector2,
+}
+
+impl WindZone {
+    pub fn contains(&self, pos: Vector2) -> bool {
+        pos.x >= self.pos.x
+            && pos.x <= self.pos.x + self.size.x
+            && pos.y >= self.pos.y
+            && pos.y <= self.pos.y + self.size.y
+    }
+
+    // speed up a ball inside the zone along the wind for `delta_time`
+    pub fn blow(&self, pos: Vector2, vel: &mut Vector2, delta_time: f32) {
+        if self.contains(pos) {
+            *vel += self.force * delta_time;
+        }
+    }
+}